@@ -1,20 +1,58 @@
-use std::iter::FromIterator;
+#[cfg(feature = "async")]
+use futures_core::Stream;
+#[cfg(feature = "rand")]
+use rand::{seq, Rng};
+use std::any::Any;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::convert::TryFrom;
+use std::fmt;
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::hash::Hash;
+use std::iter::{FromIterator, Peekable, Take};
+#[cfg(feature = "async")]
+use std::mem;
+use std::ops::{Add, Bound, Index, RangeBounds};
+#[cfg(feature = "async")]
+use std::pin::Pin;
 use std::sync::Arc;
+#[cfg(feature = "async")]
+use std::task::{Context, Poll};
 
 /// An [`Arc`]-based functional random access list.
 ///
 /// [`Arc`]: https://doc.rust-lang.org/stable/std/sync/struct.Arc.html
-#[derive(Hash, Debug, PartialEq, Eq)]
-pub struct Fral<T> {
+#[derive(Hash, Debug)]
+pub struct Fral<T: ?Sized> {
     size: usize,
-    pair: Arc<Pair<T>>,
+    // `None` iff the list is empty, so that empty lists need no allocation.
+    pair: Option<Arc<Pair<T>>>,
 }
-impl<T> Fral<T> {
+impl<T: ?Sized> Fral<T> {
+    /// The empty list. It does not allocate, so it can be used in constants and statics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// static EMPTY: Fral<u8> = Fral::EMPTY;
+    ///
+    /// assert!(EMPTY.is_empty());
+    /// assert_eq!(EMPTY.cons(1).len(), 1);
+    /// ```
+    pub const EMPTY: Fral<T> = Fral::new();
     /// Construct an empty list.
-    pub fn new() -> Fral<T> {
-        Self::default()
+    ///
+    /// Time: O(1), without allocating
+    pub const fn new() -> Fral<T> {
+        Fral {
+            size: 0,
+            pair: None,
+        }
     }
-    /// Returns a reference to an element, or `None` if it is out of bounds.
+    /// Construct a list of `n` copies of `x`. Every tree of a given size is the same, and so is
+    /// every subtree within it, so the list is made of only O(log n) distinct nodes.
     ///
     /// Time: O(log n)
     ///
@@ -23,288 +61,6237 @@ impl<T> Fral<T> {
     /// ```
     /// # use fral::Fral;
     /// # use std::sync::Arc;
-    /// let f: Fral<_> = vec![7, 0, 17].into_iter().rev().collect();
-    /// assert_eq!(f.get(2), Some(Arc::new(17)));
+    /// let f = Fral::repeat(0u64, 1 << 40);
+    /// assert_eq!(f.len(), 1 << 40);
+    /// assert_eq!(f.get(123_456_789), Some(Arc::new(0)));
     /// ```
-    pub fn get(&self, index: usize) -> Option<Arc<T>> {
-        self.pair.get(index)
+    pub fn repeat<R: AsArc<T>>(x: R, n: usize) -> Fral<T> {
+        Fral::from_pair(n, Arc::new(Pair::repeat(&x.as_arc(), n)))
     }
-    /// Insert an element at the front of the list.
+    /// Construct a list from a seed, where `f` produces each element along with the next seed,
+    /// until it returns `None`. The first element produced is at the front of the list, and the
+    /// trees are built directly rather than by consing.
     ///
-    /// Time: O(1)
-    pub fn cons<R>(&self, x: R) -> Fral<T>
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let powers: Fral<u32> = Fral::unfold(1, |x| if x < 100 { Some((x, x * 3)) } else { None });
+    /// assert_eq!(powers.iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 3, 9, 27, 81]);
+    /// ```
+    pub fn unfold<S, R, F>(seed: S, mut f: F) -> Fral<T>
     where
+        F: FnMut(S) -> Option<(R, S)>,
         R: AsArc<T>,
     {
-        Fral {
-            size: 1 + self.size,
-            pair: Arc::new(self.pair.cons(x.as_arc())),
+        let mut items = Vec::new();
+        let mut seed = seed;
+        while let Some((x, next)) = f(seed) {
+            items.push(x.as_arc());
+            seed = next;
         }
+        Fral::from_slice(&items)
     }
-    /// Get the head and tail of the list.
+    /// Concatenate many lists, in order. The elements of all but the last nonempty list are
+    /// consed onto it, right to left, so it is shared entirely.
     ///
-    /// Time: O(1)
-    pub fn uncons(&self) -> Option<(Arc<T>, Fral<T>)> {
-        let size = self.size.wrapping_sub(1);
-        self.pair.uncons().map(|(x, pair)| (x, Fral { size, pair }))
-    }
-    /// Returns true iff the list contains no elements.
+    /// Time: O(m + k), for m elements before the last nonempty list and k lists
     ///
-    /// Time: O(1)
-    pub fn is_empty(&self) -> bool {
-        self.size == 0
-    }
-    /// Get the number of items in the list.
+    /// # Examples
     ///
-    /// Time: O(1)
-    pub fn len(&self) -> usize {
-        self.size
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 2].into_iter().rev().collect();
+    /// let g: Fral<_> = vec![3, 4].into_iter().rev().collect();
+    /// let all = Fral::concat_all(vec![f.clone(), Fral::new(), g, f]);
+    /// assert_eq!(all.iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 2, 3, 4, 1, 2]);
+    /// ```
+    pub fn concat_all<I>(lists: I) -> Fral<T>
+    where
+        I: IntoIterator<Item = Fral<T>>,
+    {
+        let mut lists: Vec<Fral<T>> = lists.into_iter().collect();
+        while lists.last().is_some_and(Fral::is_empty) {
+            lists.pop();
+        }
+        let last = match lists.pop() {
+            Some(last) => last,
+            None => return Fral::new(),
+        };
+        let items: Vec<_> = lists.iter().flat_map(Fral::iter).collect();
+        last.extend_front(items)
     }
-    pub fn iter(&self) -> Iter<T> {
-        Iter { fral: self.clone() }
+    fn from_pair(size: usize, pair: Arc<Pair<T>>) -> Fral<T> {
+        let pair = if size == 0 { None } else { Some(pair) };
+        Fral { size, pair }
     }
-}
-impl<T> Clone for Fral<T> {
-    fn clone(&self) -> Fral<T> {
-        Fral {
-            size: self.size,
-            pair: self.pair.clone(),
+    fn pair(&self) -> &Pair<T> {
+        match self.pair {
+            Some(ref pair) => pair,
+            None => &Nil,
         }
     }
-}
-impl<T> Default for Fral<T> {
-    fn default() -> Fral<T> {
-        Fral {
-            size: 0,
-            pair: Arc::new(Nil),
+    /// Build a list holding the items in order.
+    ///
+    /// Time: O(n)
+    fn from_slice(items: &[Arc<T>]) -> Fral<T> {
+        Fral::from_pair(items.len(), Arc::new(Pair::from_slice(items)))
+    }
+    fn out_of_bounds(&self, index: usize) -> IndexOutOfBounds {
+        IndexOutOfBounds {
+            index,
+            len: self.size,
         }
     }
-}
-impl<T> IntoIterator for Fral<T> {
-    type Item = Arc<T>;
-    type IntoIter = Iter<T>;
-    fn into_iter(self) -> Iter<T> {
-        Iter { fral: self }
+    /// The first `n` elements of the list, where `n` is at most the length.
+    ///
+    /// Time: O(n)
+    fn prefix(&self, n: usize) -> Fral<T> {
+        self.prefix_then(n, &[])
     }
-}
-/// This is done with repeated `cons`, so you may intend to reverse your iterator first.
-///
-/// # Examples
-///
-/// ```
-/// # use fral::Fral;
-/// # use std::sync::Arc;
-/// let items = vec![1, 2, 3];
-/// let f: Fral<_> = items.into_iter().collect();
-///
-/// // the first item in f is the last item of the iterator
-/// assert_eq!(f.get(0), Some(Arc::new(3)));
-/// ```
-impl<T, R: AsArc<T>> FromIterator<R> for Fral<T> {
-    fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> Fral<T> {
-        let mut f = Fral::new();
-        for x in iter {
-            f = f.cons(x);
+    /// The first `n` elements of the list, where `n` is at most the length, followed by `back`.
+    /// Leading trees which are also in the canonical decomposition of the new length are shared,
+    /// and the rest are rebuilt.
+    ///
+    /// Time: O(n + m), for `back` of length m
+    fn prefix_then(&self, n: usize, back: &[Arc<T>]) -> Fral<T> {
+        if n == self.size && back.is_empty() {
+            return self.clone();
         }
-        f
-    }
-}
-
-use self::Pair::*;
-#[derive(Clone, Hash, Debug, PartialOrd, Ord, PartialEq, Eq)]
-enum Pair<T> {
-    Nil,
-    Cons((usize, Arc<Tree<T>>), Arc<Pair<T>>),
-}
-impl<T> Pair<T> {
-    fn get(&self, index: usize) -> Option<Arc<T>> {
-        match *self {
-            Nil => None,
-            Cons((size, ref tree), ref cdr) => {
-                if index < size {
-                    tree.lookup(size, index)
-                } else {
-                    cdr.get(index - size)
-                }
+        let mut sizes = Pair::<T>::decompose(n + back.len());
+        let mut shared = Vec::new();
+        let mut shared_len = 0;
+        let mut pair = self.pair();
+        while let Cons((size, ref tree), ref rest) = *pair {
+            if sizes.last() != Some(&size) || shared_len + size > n {
+                break;
             }
+            sizes.pop();
+            shared.push((size, tree.clone()));
+            shared_len += size;
+            pair = rest;
         }
-    }
-    fn cons(&self, x: Arc<T>) -> Self {
-        match *self {
-            Nil => Cons((1, Arc::new(Leaf(x))), Arc::new(Nil)),
-            Cons((size1, ref t1), ref nxt) => match **nxt {
-                Cons((size2, ref t2), ref rest) => {
-                    if size1 == size2 {
-                        Cons(
-                            (1 + size1 + size2, Arc::new(Node(x, t1.clone(), t2.clone()))),
-                            rest.clone(),
-                        )
-                    } else {
-                        Cons(
-                            (1, Arc::new(Leaf(x))),
-                            Arc::new(Cons(
-                                (size1, t1.clone()),
-                                Arc::new(Cons((size2, t2.clone()), rest.clone())),
-                            )),
-                        )
-                    }
-                }
-                Nil => Cons(
-                    (1, Arc::new(Leaf(x))),
-                    Arc::new(Cons((size1, t1.clone()), Arc::new(Nil))),
-                ),
-            },
+        let len = n - shared_len;
+        let mut items = Vec::with_capacity(len + back.len());
+        if len > 0 {
+            pair.all(&mut |x| {
+                items.push(x.clone());
+                items.len() < len
+            });
         }
-    }
-    fn uncons(&self) -> Option<(Arc<T>, Arc<Self>)> {
-        match *self {
-            Nil => None,
-            Cons((size, ref t), ref rest) => match **t {
-                Leaf(ref x) => Some((x.clone(), rest.clone())),
-                Node(ref x, ref t1, ref t2) => {
-                    let half = size / 2;
-                    Some((
-                        x.clone(),
-                        Arc::new(Cons(
-                            (half, t1.clone()),
-                            Arc::new(Cons((half, t2.clone()), rest.clone())),
-                        )),
-                    ))
-                }
-            },
+        items.extend_from_slice(back);
+        let mut pair = Pair::from_slice(&items);
+        for tree in shared.into_iter().rev() {
+            pair = Cons(tree, Arc::new(pair));
         }
+        Fral::from_pair(n + back.len(), Arc::new(pair))
     }
-}
-
-use self::Tree::*;
-#[derive(Clone, Hash, Debug, PartialOrd, Ord, PartialEq, Eq)]
-enum Tree<T> {
-    Leaf(Arc<T>),
-    Node(Arc<T>, Arc<Tree<T>>, Arc<Tree<T>>),
-}
-impl<T> Tree<T> {
-    fn lookup(&self, size: usize, index: usize) -> Option<Arc<T>> {
-        match (index, self) {
-            (0, &Leaf(ref x)) | (0, &Node(ref x, _, _)) => Some(x.clone()),
-            (_, &Leaf(_)) => None,
-            (i, &Node(_, ref t1, ref t2)) => {
-                let half = size / 2;
-                if i <= half {
-                    t1.lookup(half, i - 1)
-                } else {
-                    t2.lookup(half, i - 1 - half)
+    /// The list without its first `n` elements, where `n` is at most the length. Whole trees are
+    /// skipped, and only the tree containing the cut is split up.
+    ///
+    /// Time: O(log n)
+    fn suffix(&self, n: usize) -> Fral<T> {
+        let size = self.size - n;
+        let mut n = n;
+        let mut pair = match self.pair {
+            Some(ref pair) => pair,
+            None => return Fral::new(),
+        };
+        loop {
+            if n == 0 {
+                return Fral::from_pair(size, pair.clone());
+            }
+            match **pair {
+                Nil => unreachable!(),
+                Cons((tsize, ref tree), ref rest) => {
+                    if n < tsize {
+                        return Fral::from_pair(
+                            size,
+                            Tree::drop_front(tree, tsize, n, rest.clone()),
+                        );
+                    }
+                    n -= tsize;
+                    pair = rest;
                 }
             }
         }
     }
-}
-
-pub struct Iter<T> {
-    fral: Fral<T>,
-}
-impl<T> Iterator for Iter<T> {
-    type Item = Arc<T>;
-    #[inline]
-    fn next(&mut self) -> Option<Self::Item> {
-        if let Some((item, fral)) = self.fral.uncons() {
-            self.fral = fral;
-            Some(item)
-        } else {
-            None
-        }
+    /// Returns a reference to an element, or `None` if it is out of bounds.
+    ///
+    /// Time: O(log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// # use std::sync::Arc;
+    /// let f: Fral<_> = vec![7, 0, 17].into_iter().rev().collect();
+    /// assert_eq!(f.get(2), Some(Arc::new(17)));
+    /// ```
+    pub fn get(&self, index: usize) -> Option<Arc<T>> {
+        self.pair().get(index).cloned()
     }
-
-    #[inline]
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        let len = self.fral.len();
-        (len, Some(len))
+    /// Returns an element, or an [`IndexOutOfBounds`] error carrying the index and length.
+    ///
+    /// Time: O(log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::{Fral, IndexOutOfBounds};
+    /// # use std::sync::Arc;
+    /// let f: Fral<_> = vec![7, 0, 17].into_iter().rev().collect();
+    /// assert_eq!(f.try_get(2), Ok(Arc::new(17)));
+    /// assert_eq!(f.try_get(3), Err(IndexOutOfBounds { index: 3, len: 3 }));
+    /// ```
+    ///
+    /// [`IndexOutOfBounds`]: struct.IndexOutOfBounds.html
+    pub fn try_get(&self, index: usize) -> Result<Arc<T>, IndexOutOfBounds> {
+        self.get(index).ok_or_else(|| self.out_of_bounds(index))
     }
-
-    #[inline]
-    fn count(self) -> usize {
-        self.len()
+    /// Returns a reference to an element, or `None` if it is out of bounds. Unlike [`get`], this
+    /// borrows the element from the list instead of cloning its `Arc`.
+    ///
+    /// Time: O(log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![7, 0, 17].into_iter().rev().collect();
+    /// assert_eq!(f.get_ref(2), Some(&17));
+    /// assert_eq!(f.get_ref(3), None);
+    /// ```
+    ///
+    /// [`get`]: #method.get
+    pub fn get_ref(&self, index: usize) -> Option<&T> {
+        self.pair().get(index).map(|x| &**x)
     }
-
-    #[inline]
-    fn last(self) -> Option<Self::Item> {
-        let len = self.fral.len();
-        self.fral.get(len - 1)
+    /// Returns a copy of an element, or `None` if it is out of bounds. The element is cloned
+    /// straight from the list, without cloning its `Arc`.
+    ///
+    /// Time: O(log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![7, 0, 17].into_iter().rev().collect();
+    /// assert_eq!(f.get_cloned(2), Some(17));
+    /// assert_eq!(f.get_cloned(3), None);
+    /// ```
+    pub fn get_cloned(&self, index: usize) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.get_ref(index).cloned()
     }
-}
-impl<T> ExactSizeIterator for Iter<T> {}
-
-/// Automatic [`Arc`] wrapping.
-///
-/// [`Arc`]: https://doc.rust-lang.org/stable/std/sync/struct.Arc.html
-pub trait AsArc<T> {
-    fn as_arc(self) -> Arc<T>;
-}
-
-impl<T> AsArc<T> for T {
-    fn as_arc(self) -> Arc<T> {
-        Arc::from(self)
+    /// Returns a mutable reference to an element, or `None` if it is out of bounds. Each node on
+    /// the path to the element, and the element itself, is copied only if it is shared with
+    /// another list, so a uniquely held list is mutated in place while other holders of shared
+    /// parts keep their versions unchanged.
+    ///
+    /// Time: O(log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let mut f: Fral<_> = vec![1, 2, 3].into_iter().rev().collect();
+    /// let snapshot = f.clone();
+    /// *f.get_mut(1).unwrap() = 20;
+    /// assert_eq!(f.iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 20, 3]);
+    /// assert_eq!(snapshot.iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// ```
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T>
+    where
+        T: Clone,
+    {
+        if index >= self.size {
+            return None;
+        }
+        self.pair.as_mut().map(|pair| Pair::get_mut(pair, index))
     }
-}
-
-impl<T> AsArc<T> for Arc<T> {
-    fn as_arc(self) -> Arc<T> {
-        self
+    /// Returns a mutable reference to an element, or `None` if it is out of bounds or if any node
+    /// on the path to it, or the element itself, is shared with another list. Nothing is ever
+    /// copied, so `T` need not be `Clone`.
+    ///
+    /// Time: O(log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let mut f: Fral<_> = vec![1, 2, 3].into_iter().rev().collect();
+    /// *f.get_mut_unique(1).unwrap() = 20;
+    /// assert_eq!(f.iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 20, 3]);
+    ///
+    /// let snapshot = f.clone();
+    /// assert!(f.get_mut_unique(1).is_none());
+    /// drop(snapshot);
+    /// assert!(f.get_mut_unique(1).is_some());
+    /// ```
+    pub fn get_mut_unique(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.size {
+            return None;
+        }
+        self.pair
+            .as_mut()
+            .and_then(|pair| Pair::get_mut_unique(pair, index))
     }
-}
-
-#[cfg(test)]
-mod tests {
+    /// Returns a reference to an element without checking that `index` is in bounds. The spine
+    /// and trees are descended without comparing against their ends; the bounds are only
+    /// checked in debug builds.
+    ///
+    /// Time: O(log n)
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than the length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![7, 0, 17].into_iter().rev().collect();
+    /// let sum: i32 = (0..f.len()).map(|i| unsafe { *f.get_unchecked(i) }).sum();
+    /// assert_eq!(sum, 24);
+    /// ```
+    pub unsafe fn get_unchecked(&self, index: usize) -> &T {
+        debug_assert!(
+            index < self.size,
+            "index {} out of bounds for list of length {}",
+            index,
+            self.size
+        );
+        self.pair().get_unchecked(index)
+    }
+    /// Returns an element counted from the back, so that `rget(0)` is the last element, or `None`
+    /// if it is out of bounds.
+    ///
+    /// Time: O(log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// # use std::sync::Arc;
+    /// let f: Fral<_> = vec![7, 0, 17].into_iter().rev().collect();
+    /// assert_eq!(f.rget(0), Some(Arc::new(17)));
+    /// assert_eq!(f.rget(2), Some(Arc::new(7)));
+    /// assert_eq!(f.rget(3), None);
+    /// ```
+    pub fn rget(&self, index: usize) -> Option<Arc<T>> {
+        if index < self.size {
+            self.get(self.size - 1 - index)
+        } else {
+            None
+        }
+    }
+    /// Look up many elements at once, returning `None` for each index that is out of bounds. The
+    /// indices are sorted and the spine is walked once, so each tree and subtree holding any of
+    /// them is descended a single time.
+    ///
+    /// Time: O(k log k + k log n), for k indices
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// # use std::sync::Arc;
+    /// let f: Fral<_> = (0..100).rev().collect();
+    /// assert_eq!(
+    ///     f.get_many(&[70, 3, 100, 3]),
+    ///     vec![Some(Arc::new(70)), Some(Arc::new(3)), None, Some(Arc::new(3))]
+    /// );
+    /// ```
+    pub fn get_many(&self, indices: &[usize]) -> Vec<Option<Arc<T>>> {
+        let mut queries: Vec<(usize, usize)> = indices
+            .iter()
+            .enumerate()
+            .filter(|&(_, &index)| index < self.size)
+            .map(|(position, &index)| (index, position))
+            .collect();
+        queries.sort_unstable();
+        let mut found = vec![None; indices.len()];
+        self.pair().lookup_many(&queries, &mut found);
+        found
+    }
+    /// Returns the first element, or `None` if the list is empty.
+    ///
+    /// Time: O(1)
+    pub fn first(&self) -> Option<Arc<T>> {
+        match *self.pair() {
+            Nil => None,
+            Cons((_, ref tree), _) => match **tree {
+                Leaf(ref x) | Node(ref x, _, _) => Some(x.clone()),
+            },
+        }
+    }
+    /// Returns the last element, or `None` if the list is empty.
+    ///
+    /// Time: O(log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// # use std::sync::Arc;
+    /// let f: Fral<_> = vec![1, 2, 3].into_iter().rev().collect();
+    /// assert_eq!(f.first(), Some(Arc::new(1)));
+    /// assert_eq!(f.last(), Some(Arc::new(3)));
+    /// assert_eq!(Fral::<u8>::new().last(), None);
+    /// ```
+    pub fn last(&self) -> Option<Arc<T>> {
+        let mut tree = self.trees().last()?.tree;
+        loop {
+            match *tree {
+                Leaf(ref x) => return Some(x.clone()),
+                Node(_, _, ref t2) => tree = t2,
+            }
+        }
+    }
+    /// Replace an element, returning the new list, or `None` if `index` is out of bounds. Only
+    /// the path to the element is copied; the rest of the structure is shared.
+    ///
+    /// Time: O(log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// # use std::sync::Arc;
+    /// let f: Fral<_> = vec![1, 2, 3].into_iter().rev().collect();
+    /// let g = f.update(1, 20).unwrap();
+    /// assert_eq!(g.get(1), Some(Arc::new(20)));
+    /// assert_eq!(f.get(1), Some(Arc::new(2)));
+    /// assert_eq!(f.update(3, 0), None);
+    /// ```
+    pub fn update<R>(&self, index: usize, x: R) -> Option<Fral<T>>
+    where
+        R: AsArc<T>,
+    {
+        if index >= self.size {
+            return None;
+        }
+        let x = x.as_arc();
+        let pair = self.pair().update(index, move |_| x);
+        Some(Fral::from_pair(self.size, Arc::new(pair)))
+    }
+    /// Replace an element, returning the new list, or an [`IndexOutOfBounds`] error carrying the
+    /// index and length.
+    ///
+    /// Time: O(log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 2, 3].into_iter().rev().collect();
+    /// assert!(f.try_update(1, 20).is_ok());
+    /// assert_eq!(
+    ///     f.try_update(5, 0).unwrap_err().to_string(),
+    ///     "index 5 out of bounds for list of length 3"
+    /// );
+    /// ```
+    ///
+    /// [`IndexOutOfBounds`]: struct.IndexOutOfBounds.html
+    pub fn try_update<R>(&self, index: usize, x: R) -> Result<Fral<T>, IndexOutOfBounds>
+    where
+        R: AsArc<T>,
+    {
+        self.update(index, x)
+            .ok_or_else(|| self.out_of_bounds(index))
+    }
+    /// Replace an element with `f` applied to it, returning the new list, or `None` if `index`
+    /// is out of bounds. The element is found and replaced in a single pass.
+    ///
+    /// Time: O(log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// # use std::sync::Arc;
+    /// let f: Fral<_> = vec![1, 2, 3].into_iter().rev().collect();
+    /// let g = f.update_with(2, |x| x * 10).unwrap();
+    /// assert_eq!(g.get(2), Some(Arc::new(30)));
+    /// assert_eq!(f.update_with(3, |x| x * 10), None);
+    /// ```
+    pub fn update_with<F>(&self, index: usize, f: F) -> Option<Fral<T>>
+    where
+        T: Sized,
+        F: FnOnce(&T) -> T,
+    {
+        if index >= self.size {
+            return None;
+        }
+        let pair = self.pair().update(index, |x| Arc::new(f(x)));
+        Some(Fral::from_pair(self.size, Arc::new(pair)))
+    }
+    /// Get a handle to an element, or `None` if `index` is out of bounds. The path to the
+    /// element is found once, so that the handle can read the element and then replace it
+    /// without searching again.
+    ///
+    /// Time: O(log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// # use std::sync::Arc;
+    /// let f: Fral<_> = vec![1, 2, 3].into_iter().rev().collect();
+    /// let at = f.at(1).unwrap();
+    /// assert_eq!(at.get(), Arc::new(2));
+    /// assert_eq!(at.modify(|x| x * 10).iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 20, 3]);
+    /// assert_eq!(at.set(0).iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 0, 3]);
+    /// assert!(f.at(3).is_none());
+    /// ```
+    pub fn at(&self, index: usize) -> Option<At<'_, T>> {
+        if index >= self.size {
+            return None;
+        }
+        let mut spine = Vec::new();
+        let mut pair = self.pair();
+        let mut i = index;
+        let (mut size, mut tree) = loop {
+            match *pair {
+                Nil => unreachable!(),
+                Cons((size, ref tree), ref rest) => {
+                    spine.push(pair);
+                    if i < size {
+                        break (size, &**tree);
+                    }
+                    i -= size;
+                    pair = rest;
+                }
+            }
+        };
+        let mut trees = Vec::new();
+        loop {
+            match *tree {
+                Node(_, ref t1, ref t2) if i > 0 => {
+                    let half = size / 2;
+                    let left = i <= half;
+                    trees.push((tree, left));
+                    if left {
+                        tree = t1;
+                        i -= 1;
+                    } else {
+                        tree = t2;
+                        i -= 1 + half;
+                    }
+                    size = half;
+                }
+                _ => break,
+            }
+        }
+        Some(At {
+            fral: self,
+            index,
+            spine,
+            trees,
+            target: tree,
+        })
+    }
+    /// Exchange two elements, returning the new list, or `None` if either index is out of
+    /// bounds. Both paths are copied in a single pass, sharing any common prefix.
+    ///
+    /// Time: O(log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 2, 3].into_iter().rev().collect();
+    /// let g = f.swap(0, 2).unwrap();
+    /// assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), vec![3, 2, 1]);
+    /// assert_eq!(f.swap(0, 3), None);
+    /// ```
+    pub fn swap(&self, i: usize, j: usize) -> Option<Fral<T>> {
+        let (i, j) = if i <= j { (i, j) } else { (j, i) };
+        let (x, y) = (self.get(i)?, self.get(j)?);
+        if i == j {
+            return Some(self.clone());
+        }
+        let pair = self.pair().update_many(0, &[(i, y), (j, x)]);
+        Some(Fral::from_pair(self.size, Arc::new(pair)))
+    }
+    /// Replace many elements at once, given as pairs of index and element. Where an index is
+    /// given more than once, the last element for it wins. Each affected tree is rebuilt once,
+    /// no matter how many of its elements change.
+    ///
+    /// Time: O(k log n), for k updates
+    ///
+    /// # Panics
+    ///
+    /// Panics if any index is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = (0..6).rev().collect();
+    /// let g = f.apply_updates(vec![(4, 40), (0, 0), (1, 10), (4, 44)]);
+    /// assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), vec![0, 10, 2, 3, 44, 5]);
+    /// ```
+    pub fn apply_updates<I, R>(&self, updates: I) -> Fral<T>
+    where
+        I: IntoIterator<Item = (usize, R)>,
+        R: AsArc<T>,
+    {
+        let mut updates: Vec<(usize, Arc<T>)> = updates
+            .into_iter()
+            .map(|(index, x)| (index, x.as_arc()))
+            .collect();
+        // the sort is stable, so after reversing, the update kept by dedup is the last one given.
+        updates.sort_by_key(|&(index, _)| index);
+        updates.reverse();
+        updates.dedup_by_key(|&mut (index, _)| index);
+        updates.reverse();
+        match updates.last() {
+            None => return self.clone(),
+            Some(&(index, _)) => assert!(
+                index < self.size,
+                "index {} out of bounds for list of length {}",
+                index,
+                self.size
+            ),
+        }
+        let pair = self.pair().update_many(0, &updates);
+        Fral::from_pair(self.size, Arc::new(pair))
+    }
+    /// Insert an element at the front of the list.
+    ///
+    /// Time: O(1)
+    pub fn cons<R>(&self, x: R) -> Fral<T>
+    where
+        R: AsArc<T>,
+    {
+        Fral {
+            size: 1 + self.size,
+            pair: Some(Arc::new(self.pair().cons(x.as_arc()))),
+        }
+    }
+    /// Insert an element at the front of the list, converting it into an `Arc` first. This
+    /// accepts anything an `Arc<T>` can be built from, such as a `Box<T>`, a `&str` or `String`
+    /// for a `Fral<str>`, or a slice or `Vec` for a `Fral<[T]>`.
+    ///
+    /// Time: O(1), after the conversion
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<str> = Fral::new().cons_from("b").cons_from(String::from("a"));
+    /// assert_eq!(&f[0], "a");
+    /// let g: Fral<[u8]> = Fral::new().cons_from(&[1, 2][..]).cons_from(vec![3]);
+    /// assert_eq!(&g[1], &[1, 2]);
+    /// ```
+    pub fn cons_from<U: Into<Arc<T>>>(&self, x: U) -> Fral<T> {
+        self.cons(x.into())
+    }
+    /// Insert a clone of an element at the front of the list.
+    ///
+    /// Time: O(1), after the clone
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let x = String::from("a");
+    /// let f = Fral::new().cons_cloned(&x);
+    /// assert_eq!(*f.get(0).unwrap(), x);
+    /// ```
+    pub fn cons_cloned(&self, x: &T) -> Fral<T>
+    where
+        T: Clone,
+    {
+        self.cons(x.clone())
+    }
+    /// Insert an element at the back of the list. The shape of a list is determined by its
+    /// length, so only the leading trees which keep their place are shared, and the rest are
+    /// rebuilt.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// # use std::sync::Arc;
+    /// let f: Fral<_> = vec![1, 2].into_iter().rev().collect();
+    /// let g = f.push_back(3);
+    /// assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// assert_eq!(g.last(), Some(Arc::new(3)));
+    /// ```
+    pub fn push_back<R>(&self, x: R) -> Fral<T>
+    where
+        R: AsArc<T>,
+    {
+        self.prefix_then(self.size, &[x.as_arc()])
+    }
+    /// Get the last element and the list without it, or `None` if the list is empty. As with
+    /// [`push_back`], only the leading trees which keep their place in the shorter list are
+    /// shared. In particular, a list made of a single tree is rebuilt entirely.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// # use std::sync::Arc;
+    /// let f: Fral<_> = vec![1, 2, 3].into_iter().rev().collect();
+    /// let (last, init) = f.pop_back().unwrap();
+    /// assert_eq!(last, Arc::new(3));
+    /// assert_eq!(init.iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 2]);
+    /// assert_eq!(Fral::<u8>::new().pop_back(), None);
+    /// ```
+    ///
+    /// [`push_back`]: #method.push_back
+    pub fn pop_back(&self) -> Option<(Arc<T>, Fral<T>)> {
+        let last = self.last()?;
+        Some((last, self.prefix(self.size - 1)))
+    }
+    /// Insert the elements of `iter` at the front of the list, in the iterator's order: the first
+    /// element yielded becomes the first element of the list. Each new spine cell points straight
+    /// at the previous one, so the existing cells are shared rather than reallocated as by
+    /// repeated [`cons`].
+    ///
+    /// Time: O(m), for m new elements
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![3, 4].into_iter().rev().collect();
+    /// let g = f.extend_front(vec![1, 2]);
+    /// assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+    /// ```
+    ///
+    /// [`cons`]: #method.cons
+    pub fn extend_front<I, R>(&self, iter: I) -> Fral<T>
+    where
+        I: IntoIterator<Item = R>,
+        R: AsArc<T>,
+    {
+        let items: Vec<_> = iter.into_iter().map(AsArc::as_arc).collect();
+        if items.is_empty() {
+            return self.clone();
+        }
+        let mut pair = self.pair.clone().unwrap_or_else(|| Arc::new(Nil));
+        for x in items.iter().rev() {
+            pair = Arc::new(Pair::cons_shared(&pair, x.clone()));
+        }
+        Fral::from_pair(self.size + items.len(), pair)
+    }
+    /// Get the head and tail of the list.
+    ///
+    /// Time: O(1)
+    pub fn uncons(&self) -> Option<(Arc<T>, Fral<T>)> {
+        let size = self.size.wrapping_sub(1);
+        self.pair()
+            .uncons()
+            .map(|(x, pair)| (x, Fral::from_pair(size, pair)))
+    }
+    /// Insert an element at the front of the list in place, replacing this list with the new
+    /// one. Other lists sharing structure with this one are unaffected.
+    ///
+    /// Time: O(1)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let mut f = Fral::new();
+    /// for x in 0..3 {
+    ///     f.cons_mut(x);
+    /// }
+    /// assert_eq!(f.iter().map(|x| *x).collect::<Vec<_>>(), vec![2, 1, 0]);
+    /// ```
+    pub fn cons_mut<R>(&mut self, x: R)
+    where
+        R: AsArc<T>,
+    {
+        *self = self.cons(x);
+    }
+    /// Remove the first element in place, replacing this list with its tail, or return `None` if
+    /// it is empty. Other lists sharing structure with this one are unaffected.
+    ///
+    /// Time: O(1)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let mut f: Fral<_> = vec![1, 2].into_iter().rev().collect();
+    /// let mut popped = Vec::new();
+    /// while let Some(x) = f.pop_front() {
+    ///     popped.push(*x);
+    /// }
+    /// assert_eq!(popped, vec![1, 2]);
+    /// assert!(f.is_empty());
+    /// ```
+    pub fn pop_front(&mut self) -> Option<Arc<T>> {
+        let (x, rest) = self.uncons()?;
+        *self = rest;
+        Some(x)
+    }
+    /// Get the list without its first element, or `None` if it is empty.
+    ///
+    /// Time: O(1)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 2, 3].into_iter().rev().collect();
+    /// assert_eq!(f.tail(), f.uncons().map(|(_, tail)| tail));
+    /// assert_eq!(Fral::<u8>::new().tail(), None);
+    /// ```
+    pub fn tail(&self) -> Option<Fral<T>> {
+        let size = self.size.wrapping_sub(1);
+        self.pair().tail().map(|pair| Fral::from_pair(size, pair))
+    }
+    /// Remove an element, returning it and the new list, or `None` if `index` is out of bounds.
+    /// Everything after the element is shared with the original list.
+    ///
+    /// Time: O(index + log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// # use std::sync::Arc;
+    /// let f: Fral<_> = vec![1, 2, 3].into_iter().rev().collect();
+    /// let (x, g) = f.remove(1).unwrap();
+    /// assert_eq!(x, Arc::new(2));
+    /// assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 3]);
+    /// assert_eq!(f.remove(3), None);
+    /// ```
+    pub fn remove(&self, index: usize) -> Option<(Arc<T>, Fral<T>)> {
+        if index >= self.size {
+            return None;
+        }
+        let mut front = Vec::with_capacity(index);
+        let mut removed = None;
+        self.pair().all(&mut |x| {
+            if front.len() < index {
+                front.push(x.clone());
+                true
+            } else {
+                removed = Some(x.clone());
+                false
+            }
+        });
+        let rest = front
+            .into_iter()
+            .rev()
+            .fold(self.suffix(index + 1), |f, x| f.cons(x));
+        removed.map(|x| (x, rest))
+    }
+    /// Remove an element, returning it and the new list, or an [`IndexOutOfBounds`] error
+    /// carrying the index and length.
+    ///
+    /// Time: O(index + log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::{Fral, IndexOutOfBounds};
+    /// let f: Fral<_> = vec![1, 2, 3].into_iter().rev().collect();
+    /// assert!(f.try_remove(2).is_ok());
+    /// assert_eq!(f.try_remove(3).unwrap_err(), IndexOutOfBounds { index: 3, len: 3 });
+    /// ```
+    ///
+    /// [`IndexOutOfBounds`]: struct.IndexOutOfBounds.html
+    pub fn try_remove(&self, index: usize) -> Result<(Arc<T>, Fral<T>), IndexOutOfBounds> {
+        self.remove(index).ok_or_else(|| self.out_of_bounds(index))
+    }
+    /// Remove an element without preserving order, returning it and the new list, or `None` if
+    /// `index` is out of bounds. The element is overwritten with the first element, which is
+    /// then unconsed, so apart from the first element moving into the gap, the order is kept.
+    ///
+    /// Time: O(log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// # use std::sync::Arc;
+    /// let f: Fral<_> = vec![1, 2, 3, 4].into_iter().rev().collect();
+    /// let (x, g) = f.swap_remove(2).unwrap();
+    /// assert_eq!(x, Arc::new(3));
+    /// assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), vec![2, 1, 4]);
+    /// assert_eq!(f.swap_remove(4), None);
+    /// ```
+    pub fn swap_remove(&self, index: usize) -> Option<(Arc<T>, Fral<T>)> {
+        let removed = self.get(index)?;
+        let (head, _) = self.uncons()?;
+        let rest = self.update(index, head)?.tail()?;
+        Some((removed, rest))
+    }
+    /// Returns true iff the list contains no elements.
+    ///
+    /// Time: O(1)
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+    /// Returns true iff both lists are the same version, sharing their whole structure. Lists
+    /// that are equal but were built separately are not the same version.
+    ///
+    /// Time: O(1)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 2].into_iter().collect();
+    /// let g = f.clone();
+    /// assert!(f.ptr_eq(&g));
+    /// assert!(!f.ptr_eq(&f.update(0, 2).unwrap()));
+    /// assert!(!f.ptr_eq(&vec![1, 2].into_iter().collect()));
+    /// ```
+    pub fn ptr_eq(&self, other: &Fral<T>) -> bool {
+        match (&self.pair, &other.pair) {
+            (Some(p1), Some(p2)) => Arc::ptr_eq(p1, p2),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+    /// Get the number of items in the list.
+    ///
+    /// Time: O(1)
+    pub fn len(&self) -> usize {
+        self.size
+    }
+    pub fn iter(&self) -> Iter<T> {
+        Iter::new(self.clone())
+    }
+    /// Iterate over references to the elements. The trees are walked in place, so unlike
+    /// [`iter`] this neither clones each element's pointer nor allocates.
+    ///
+    /// Time: O(1) per element
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 2, 3].into_iter().rev().collect();
+    /// assert_eq!(f.iter_ref().sum::<i32>(), 6);
+    /// assert_eq!(f.iter_ref().collect::<Vec<_>>(), vec![&1, &2, &3]);
+    /// ```
+    ///
+    /// [`iter`]: #method.iter
+    pub fn iter_ref(&self) -> IterRef<'_, T> {
+        IterRef {
+            pair: self.pair(),
+            stack: [None; MAX_DEPTH],
+            depth: 0,
+            len: self.size,
+        }
+    }
+    /// Iterate over the elements from `index` onwards, which is empty if `index` is past the end.
+    /// Whole trees before `index` are skipped, as with [`drop_front`], rather than visited.
+    ///
+    /// Time: O(log n) to start, then O(1) per element
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = (0..100).rev().collect();
+    /// assert_eq!(f.iter_from(97).map(|x| *x).collect::<Vec<_>>(), vec![97, 98, 99]);
+    /// assert_eq!(f.iter_from(100).next(), None);
+    /// ```
+    ///
+    /// [`drop_front`]: #method.drop_front
+    pub fn iter_from(&self, index: usize) -> Iter<T> {
+        Iter::new(self.drop_front(index))
+    }
+    /// Build a list from the items of a stream as they arrive, consing each in turn like
+    /// [`collect`] does, so the last item ends up at the front.
+    ///
+    /// [`collect`]: https://doc.rust-lang.org/stable/std/iter/trait.Iterator.html#method.collect
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate fral;
+    /// # extern crate futures;
+    /// # use fral::Fral;
+    /// # fn main() {
+    /// use futures::executor::block_on;
+    /// use futures::stream;
+    ///
+    /// let f: Fral<_> = block_on(Fral::from_stream(stream::iter(1..=3)));
+    /// assert_eq!(f.iter().map(|x| *x).collect::<Vec<_>>(), vec![3, 2, 1]);
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn from_stream<S>(stream: S) -> FromStream<S, T>
+    where
+        S: Stream + Unpin,
+        S::Item: AsArc<T>,
+    {
+        FromStream {
+            stream,
+            fral: Fral::new(),
+        }
+    }
+    /// Build a list from the items of a stream, keeping them in the order they arrive. The
+    /// list is built in bulk once the stream ends.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate fral;
+    /// # extern crate futures;
+    /// # use fral::Fral;
+    /// # fn main() {
+    /// use futures::executor::block_on;
+    /// use futures::stream;
+    ///
+    /// let f: Fral<_> = block_on(Fral::from_stream_ordered(stream::iter(1..=3)));
+    /// assert_eq!(f.iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn from_stream_ordered<S>(stream: S) -> FromStreamOrdered<S, T>
+    where
+        S: Stream + Unpin,
+        S::Item: AsArc<T>,
+    {
+        FromStreamOrdered {
+            stream,
+            items: Vec::new(),
+        }
+    }
+    /// Iterate over the index and [`ElementId`] of each element.
+    ///
+    /// [`ElementId`]: struct.ElementId.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec!["a", "b"].into_iter().collect();
+    /// let g = f.cons("a");
+    /// let ids: Vec<_> = g.iter_ids().map(|(_, id)| id).collect();
+    /// // the snapshots share the elements of `f`
+    /// assert_eq!(&ids[1..], &f.iter_ids().map(|(_, id)| id).collect::<Vec<_>>()[..]);
+    /// // equal values in separate allocations are distinct
+    /// assert_ne!(ids[0], ids[2]);
+    /// ```
+    pub fn iter_ids(&self) -> impl Iterator<Item = (usize, ElementId)> {
+        self.iter().map(|x| ElementId(address(&x))).enumerate()
+    }
+    /// Iterate over the complete binary trees which make up the list, from front to back.
+    ///
+    /// Each tree holds its elements in preorder: the [`value`] at its root comes first, followed
+    /// by the elements of its [`left`] and then its [`right`] subtree.
+    ///
+    /// [`value`]: struct.TreeRef.html#method.value
+    /// [`left`]: struct.TreeRef.html#method.left
+    /// [`right`]: struct.TreeRef.html#method.right
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::{Fral, TreeRef};
+    /// fn sum(tree: TreeRef<u32>) -> u32 {
+    ///     **tree.value() + tree.left().map_or(0, sum) + tree.right().map_or(0, sum)
+    /// }
+    ///
+    /// let f: Fral<u32> = (1..=10).collect();
+    /// assert_eq!(f.trees().map(|t| t.size()).collect::<Vec<_>>(), vec![3, 7]);
+    /// assert_eq!(f.trees().map(sum).sum::<u32>(), 55);
+    /// ```
+    pub fn trees(&self) -> Trees<'_, T> {
+        Trees { pair: self.pair() }
+    }
+    /// Iterate over the elements in groups of `size`, in order. The last group is shorter if the
+    /// length is not a multiple of `size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = (0..5).rev().collect();
+    /// let chunks: Vec<Vec<_>> = f.chunks(2).map(|c| c.iter().map(|x| **x).collect()).collect();
+    /// assert_eq!(chunks, vec![vec![0, 1], vec![2, 3], vec![4]]);
+    /// ```
+    pub fn chunks(&self, size: usize) -> Chunks<T> {
+        assert!(size != 0, "chunk size must be nonzero");
+        Chunks {
+            iter: self.iter(),
+            size,
+        }
+    }
+    /// Iterate over every run of `size` consecutive elements, in order. Nothing is yielded if
+    /// the list is shorter than `size`. The elements are visited once, so each window costs
+    /// O(size) to produce rather than O(size log n).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = (0..4).rev().collect();
+    /// let sums: Vec<_> = f.windows(2).map(|w| *w[0] + *w[1]).collect();
+    /// assert_eq!(sums, vec![1, 3, 5]);
+    /// ```
+    pub fn windows(&self, size: usize) -> Windows<T> {
+        assert!(size != 0, "window size must be nonzero");
+        Windows {
+            iter: self.iter(),
+            window: VecDeque::with_capacity(size),
+            size,
+        }
+    }
+    /// Iterate over the elements in arrays of `N`. If the length is not a multiple of `N`, the
+    /// last few elements are not yielded and are available from [`remainder`] instead.
+    ///
+    /// [`remainder`]: struct.ArrayChunks.html#method.remainder
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec!["a", "1", "b", "2", "c"].into_iter().rev().collect();
+    /// let mut pairs = f.array_chunks::<2>();
+    /// for [k, v] in pairs.by_ref() {
+    ///     assert_eq!(k.len(), v.len());
+    /// }
+    /// assert_eq!(pairs.remainder().len(), 1);
+    /// ```
+    pub fn array_chunks<const N: usize>(&self) -> ArrayChunks<T, N> {
+        assert!(N != 0, "chunk size must be nonzero");
+        ArrayChunks {
+            iter: self.iter(),
+            remainder: Vec::new(),
+        }
+    }
+    /// Combine the elements from front to back with `f`, or return `None` if the list is empty.
+    /// A single element is returned as it is, so no identity element or cloning is needed.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// # use std::sync::Arc;
+    /// let f: Fral<_> = vec![3, 9, 2].into_iter().collect();
+    /// assert_eq!(f.reduce(|a, b| *a.max(b)), Some(Arc::new(9)));
+    /// assert_eq!(f.reduce(|a, b| a - b), Some(Arc::new(2 - 9 - 3)));
+    /// assert_eq!(Fral::new().cons(4).reduce(|a, b| a - b), Some(Arc::new(4)));
+    /// ```
+    pub fn reduce<F>(&self, f: F) -> Option<Arc<T>>
+    where
+        T: Sized,
+        F: Fn(&T, &T) -> T,
+    {
+        let mut first: Option<Arc<T>> = None;
+        let mut acc = None;
+        self.pair().all(&mut |x| {
+            acc = match (acc.take(), &first) {
+                (Some(acc), _) => Some(f(&acc, x)),
+                (None, Some(first)) => Some(f(first, x)),
+                (None, None) => {
+                    first = Some(x.clone());
+                    None
+                }
+            };
+            true
+        });
+        acc.map(Arc::new).or(first)
+    }
+    /// Returns true iff the list contains an element equal to `x`. The trees are walked by
+    /// reference, stopping at the first match.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 2, 3].into_iter().collect();
+    /// assert!(f.contains(&2));
+    /// assert!(!f.contains(&4));
+    /// ```
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        !self.pair().all(&mut |y| **y != *x)
+    }
+    /// Returns the index of the first element satisfying `pred`, or `None` if there is none. The
+    /// trees are walked by reference, stopping at the first match.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 4, 2, 8].into_iter().rev().collect();
+    /// assert_eq!(f.position(|x| *x > 3), Some(1));
+    /// assert_eq!(f.position(|x| *x > 8), None);
+    /// ```
+    pub fn position<F>(&self, mut pred: F) -> Option<usize>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut index = 0;
+        if self.pair().all(&mut |x| {
+            if pred(x) {
+                return false;
+            }
+            index += 1;
+            true
+        }) {
+            None
+        } else {
+            Some(index)
+        }
+    }
+    /// Returns the index of the last element satisfying `pred`, or `None` if there is none. The
+    /// trees are walked from the back, so matches near the end of a long list are found without
+    /// visiting its front.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 4, 2, 8].into_iter().rev().collect();
+    /// assert_eq!(f.rposition(|x| *x < 3), Some(2));
+    /// assert_eq!(f.rposition(|x| *x > 8), None);
+    /// ```
+    pub fn rposition<F>(&self, mut pred: F) -> Option<usize>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut index = self.len();
+        if self.pair().rall(&mut |x| {
+            index -= 1;
+            !pred(x)
+        }) {
+            None
+        } else {
+            Some(index)
+        }
+    }
+    /// Returns the index of the first element equal to `x`, or `None` if there is none.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec!["a", "b", "a"].into_iter().rev().collect();
+    /// assert_eq!(f.index_of(&"a"), Some(0));
+    /// assert_eq!(f.index_of(&"b"), Some(1));
+    /// assert_eq!(f.index_of(&"c"), None);
+    /// ```
+    pub fn index_of(&self, x: &T) -> Option<usize>
+    where
+        T: PartialEq,
+    {
+        self.position(|y| *y == *x)
+    }
+    /// Returns the index of the first element that is the same allocation as `x`, or `None` if
+    /// there is none. Elements are compared with [`Arc::ptr_eq`], so `T` need not be comparable.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// # use std::sync::Arc;
+    /// let x = Arc::new(1);
+    /// let f: Fral<_> = Fral::new().cons(x.clone()).cons(1);
+    /// assert_eq!(f.position_by_ptr(&x), Some(1));
+    /// assert_eq!(f.position_by_ptr(&Arc::new(1)), None);
+    /// ```
+    ///
+    /// [`Arc::ptr_eq`]: https://doc.rust-lang.org/stable/std/sync/struct.Arc.html#method.ptr_eq
+    pub fn position_by_ptr(&self, x: &Arc<T>) -> Option<usize> {
+        let mut index = 0;
+        if self.pair().all(&mut |y| {
+            if Arc::ptr_eq(x, y) {
+                return false;
+            }
+            index += 1;
+            true
+        }) {
+            None
+        } else {
+            Some(index)
+        }
+    }
+    /// Returns true iff `other` is a prefix of this list. Leading trees of the same size are
+    /// compared whole, and subtrees shared by both lists are equal without looking at their
+    /// elements.
+    ///
+    /// Time: O(m), for `other` of length m, and O(log n) if `other` shares its trees with this list
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 2, 3].into_iter().rev().collect();
+    /// let g: Fral<_> = vec![1, 2].into_iter().rev().collect();
+    /// assert!(f.starts_with(&g));
+    /// assert!(!g.starts_with(&f));
+    /// ```
+    pub fn starts_with(&self, other: &Fral<T>) -> bool
+    where
+        T: PartialEq,
+    {
+        if other.size > self.size {
+            return false;
+        }
+        let (mut f, mut g) = (self.clone(), other.clone());
+        loop {
+            let (size, r1, r2) = match (f.pair(), g.pair()) {
+                (_, &Nil) => return true,
+                (Cons((size, t1), r1), Cons((gsize, t2), r2)) if size == gsize => {
+                    if !(Arc::ptr_eq(t1, t2) || t1.eq_shared(t2)) {
+                        return false;
+                    }
+                    if Arc::ptr_eq(r1, r2) {
+                        return true;
+                    }
+                    (*size, r1.clone(), r2.clone())
+                }
+                _ => break,
+            };
+            f = Fral::from_pair(f.size - size, r1);
+            g = Fral::from_pair(g.size - size, r2);
+        }
+        // the decompositions have diverged, so compare the rest element by element.
+        f.iter().zip(g.iter()).all(|(x, y)| *x == *y)
+    }
+    /// Returns true iff `other` is a suffix of this list. The suffix of this list is taken without
+    /// copying, and has the same shape as `other`, so subtrees shared by both lists are equal
+    /// without looking at their elements.
+    ///
+    /// Time: O(m + log n), for `other` of length m, and O(log n) if `other` shares its trees with
+    /// this list
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let g: Fral<_> = vec![2, 3].into_iter().rev().collect();
+    /// let f = g.cons(1);
+    /// assert!(f.ends_with(&g));
+    /// assert!(!g.ends_with(&f));
+    /// ```
+    pub fn ends_with(&self, other: &Fral<T>) -> bool
+    where
+        T: PartialEq,
+    {
+        other.size <= self.size && self.suffix(self.size - other.size).eq_shared(other)
+    }
+    /// Returns the rest of this list if it starts with `other`, or `None` otherwise.
+    ///
+    /// Time: O(m + log n), for `other` of length m
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 2, 3].into_iter().rev().collect();
+    /// let g: Fral<_> = vec![1].into_iter().collect();
+    /// let rest = f.strip_prefix(&g).unwrap();
+    /// assert_eq!(rest.iter().map(|x| *x).collect::<Vec<_>>(), vec![2, 3]);
+    /// assert!(rest.strip_prefix(&g).is_none());
+    /// ```
+    pub fn strip_prefix(&self, other: &Fral<T>) -> Option<Fral<T>>
+    where
+        T: PartialEq,
+    {
+        if self.starts_with(other) {
+            Some(self.suffix(other.size))
+        } else {
+            None
+        }
+    }
+    /// Compare with a list, where shared subtrees are equal without looking at their elements.
+    fn eq_shared(&self, other: &Fral<T>) -> bool
+    where
+        T: PartialEq,
+    {
+        // the shape is determined by the length, so the trees line up.
+        self.size == other.size
+            && match (&self.pair, &other.pair) {
+                (Some(p1), Some(p2)) => Arc::ptr_eq(p1, p2) || p1.eq_shared(p2),
+                _ => true,
+            }
+    }
+    /// Get the elements in ascending order. The sort is stable, and only the element pointers
+    /// are moved.
+    ///
+    /// Time: O(n log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![3, 1, 2].into_iter().rev().collect();
+    /// assert_eq!(f.sorted().iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// ```
+    pub fn sorted(&self) -> Fral<T>
+    where
+        T: Ord,
+    {
+        self.sorted_by(T::cmp)
+    }
+    /// Get the elements sorted with the comparator `compare`. The sort is stable.
+    ///
+    /// Time: O(n log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![3, 1, 2].into_iter().rev().collect();
+    /// let g = f.sorted_by(|a, b| b.cmp(a));
+    /// assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), vec![3, 2, 1]);
+    /// ```
+    pub fn sorted_by<F>(&self, mut compare: F) -> Fral<T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut items: Vec<_> = self.iter().collect();
+        items.sort_by(|a, b| compare(a, b));
+        Fral::from_slice(&items)
+    }
+    /// Get the elements sorted by the key `f` extracts from each. The sort is stable.
+    ///
+    /// Time: O(n log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec!["ccc", "a", "bb"].into_iter().rev().collect();
+    /// let g = f.sorted_by_key(|s| s.len());
+    /// assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), vec!["a", "bb", "ccc"]);
+    /// ```
+    pub fn sorted_by_key<K, F>(&self, mut f: F) -> Fral<T>
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.sorted_by(|a, b| f(a).cmp(&f(b)))
+    }
+    /// Merge this sorted list with another sorted list, keeping the result sorted. Equal elements
+    /// from this list come first. Whichever list runs out last has its remaining elements shared
+    /// as the tail of the result.
+    ///
+    /// Time: O(n + m)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 4, 6].into_iter().rev().collect();
+    /// let g: Fral<_> = vec![2, 3, 7, 8].into_iter().rev().collect();
+    /// let merged = f.merge(&g);
+    /// assert_eq!(merged.iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 2, 3, 4, 6, 7, 8]);
+    /// ```
+    pub fn merge(&self, other: &Fral<T>) -> Fral<T>
+    where
+        T: Ord,
+    {
+        self.merge_by(other, T::cmp)
+    }
+    /// Merge this list with another, both sorted with respect to the comparator `compare`,
+    /// keeping the result sorted. Equal elements from this list come first. Whichever list runs
+    /// out last has its remaining elements shared as the tail of the result.
+    ///
+    /// Time: O(n + m)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![9, 5, 1].into_iter().rev().collect();
+    /// let g: Fral<_> = vec![8, 2].into_iter().rev().collect();
+    /// let merged = f.merge_by(&g, |a, b| b.cmp(a));
+    /// assert_eq!(merged.iter().map(|x| *x).collect::<Vec<_>>(), vec![9, 8, 5, 2, 1]);
+    /// ```
+    pub fn merge_by<F>(&self, other: &Fral<T>, mut compare: F) -> Fral<T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let (mut xs, mut ys) = (self.iter().peekable(), other.iter().peekable());
+        let mut merged = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        let tail = loop {
+            let take_x = match (xs.peek(), ys.peek()) {
+                (Some(x), Some(y)) => compare(x, y) != Ordering::Greater,
+                (_, None) => break self.suffix(i),
+                (None, _) => break other.suffix(j),
+            };
+            if take_x {
+                merged.push(xs.next().unwrap());
+                i += 1;
+            } else {
+                merged.push(ys.next().unwrap());
+                j += 1;
+            }
+        };
+        tail.extend_front(merged)
+    }
+    /// Alternate the elements of this list and `other`, starting with this one, followed by the
+    /// rest of whichever is longer. That rest is shared with the longer list.
+    ///
+    /// Time: O(m + log n), for a shorter list of length m
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 3].into_iter().rev().collect();
+    /// let g: Fral<_> = vec![2, 4, 5, 6].into_iter().rev().collect();
+    /// assert_eq!(f.interleave(&g).iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6]);
+    /// ```
+    pub fn interleave(&self, other: &Fral<T>) -> Fral<T> {
+        let n = self.size.min(other.size);
+        let mut items = Vec::with_capacity(2 * n);
+        for (x, y) in self.iter().zip(other.iter()) {
+            items.push(x);
+            items.push(y);
+        }
+        let rest = if self.size > n {
+            self.suffix(n)
+        } else {
+            other.suffix(n)
+        };
+        rest.extend_front(items)
+    }
+    /// Search a sorted list for `x`, returning `Ok` with its index if it is found, or `Err` with
+    /// the index where it could be inserted to keep the list sorted. If several elements match,
+    /// any one of their indices may be returned.
+    ///
+    /// Time: O(log² n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 3, 5].into_iter().rev().collect();
+    /// assert_eq!(f.binary_search(&3), Ok(1));
+    /// assert_eq!(f.binary_search(&4), Err(2));
+    /// ```
+    pub fn binary_search(&self, x: &T) -> Result<usize, usize>
+    where
+        T: Ord,
+    {
+        self.binary_search_by(|y| y.cmp(x))
+    }
+    /// Search a sorted list with the comparator `f`, which returns how an element is ordered
+    /// relative to the target. See [`binary_search`].
+    ///
+    /// [`binary_search`]: #method.binary_search
+    ///
+    /// Time: O(log² n)
+    pub fn binary_search_by<F>(&self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> Ordering,
+    {
+        let (mut low, mut high) = (0, self.size);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            // `mid` is in bounds.
+            match f(&self.get(mid).unwrap()) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => return Ok(mid),
+            }
+        }
+        Err(low)
+    }
+    /// Search a list sorted by the key `f` extracts from each element for the key `key`. See
+    /// [`binary_search`].
+    ///
+    /// [`binary_search`]: #method.binary_search
+    ///
+    /// Time: O(log² n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![(1, 'a'), (3, 'b')].into_iter().rev().collect();
+    /// assert_eq!(f.binary_search_by_key(&3, |&(k, _)| k), Ok(1));
+    /// ```
+    pub fn binary_search_by_key<K, F>(&self, key: &K, mut f: F) -> Result<usize, usize>
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.binary_search_by(|x| f(x).cmp(key))
+    }
+    /// Returns true iff the elements are in ascending order.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 2, 2, 3].into_iter().rev().collect();
+    /// assert!(f.is_sorted());
+    /// assert!(!f.cons(4).is_sorted());
+    /// ```
+    pub fn is_sorted(&self) -> bool
+    where
+        T: PartialOrd,
+    {
+        self.is_sorted_by(|a, b| a <= b)
+    }
+    /// Returns true iff `in_order` returns true for every pair of adjacent elements. The trees
+    /// are walked by reference and the walk stops at the first pair out of order.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![3, 2, 1].into_iter().rev().collect();
+    /// assert!(f.is_sorted_by(|a, b| a > b));
+    /// ```
+    pub fn is_sorted_by<F>(&self, mut in_order: F) -> bool
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        let mut prev: Option<&Arc<T>> = None;
+        self.pair().all(&mut |x| {
+            let ok = prev.is_none_or(|prev| in_order(prev, x));
+            prev = Some(x);
+            ok
+        })
+    }
+    /// Get the `k`-th smallest element (counting from zero), or `None` if `k` is out of bounds.
+    /// The list is not sorted; only its element pointers are copied and partially reordered.
+    ///
+    /// Time: O(n) expected
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// # use std::sync::Arc;
+    /// let f: Fral<_> = vec![5, 1, 4, 2, 3].into_iter().collect();
+    /// assert_eq!(f.select_nth(0), Some(Arc::new(1)));
+    /// assert_eq!(f.select_nth(3), Some(Arc::new(4)));
+    /// assert_eq!(f.select_nth(5), None);
+    /// ```
+    pub fn select_nth(&self, k: usize) -> Option<Arc<T>>
+    where
+        T: Ord,
+    {
+        self.select_nth_by(k, T::cmp)
+    }
+    /// Get the `k`-th smallest element (counting from zero) with respect to the comparator
+    /// `compare`, or `None` if `k` is out of bounds. This is a quickselect over the element
+    /// pointers, so the list is never fully sorted.
+    ///
+    /// Time: O(n) expected
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// # use std::sync::Arc;
+    /// let f: Fral<f64> = vec![0.5, 2.5, 1.5, 3.5].into_iter().collect();
+    /// let p75 = f.select_nth_by(f.len() * 3 / 4, |a, b| a.partial_cmp(b).unwrap());
+    /// assert_eq!(p75, Some(Arc::new(3.5)));
+    /// ```
+    pub fn select_nth_by<F>(&self, k: usize, mut compare: F) -> Option<Arc<T>>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        if k >= self.size {
+            return None;
+        }
+        let mut items: Vec<Arc<T>> = self.iter().collect();
+        let (_, nth, _) = items.select_nth_unstable_by(k, |a, b| compare(a, b));
+        Some(nth.clone())
+    }
+    /// Get the median element, or `None` if the list is empty. For an even number of elements,
+    /// this is the lower of the two middle elements.
+    ///
+    /// Time: O(n) expected
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// # use std::sync::Arc;
+    /// let f: Fral<_> = vec![7, 1, 4, 9].into_iter().collect();
+    /// assert_eq!(f.median(), Some(Arc::new(4)));
+    /// assert_eq!(f.cons(5).median(), Some(Arc::new(5)));
+    /// ```
+    pub fn median(&self) -> Option<Arc<T>>
+    where
+        T: Ord,
+    {
+        self.select_nth(self.size.saturating_sub(1) / 2)
+    }
+    /// Get a view of a range of the list, without copying any of its structure.
+    ///
+    /// Time: O(1)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is decreasing or extends past the end of the list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// # use std::sync::Arc;
+    /// let f: Fral<_> = (0..10).rev().collect();
+    /// let view = f.view(2..8);
+    /// assert_eq!(view.len(), 6);
+    /// assert_eq!(view.get(0), Some(Arc::new(2)));
+    /// assert_eq!(view.view(1..).iter().map(|x| *x).collect::<Vec<_>>(), vec![3, 4, 5, 6, 7]);
+    /// ```
+    pub fn view<R: RangeBounds<usize>>(&self, range: R) -> FralSlice<T> {
+        let (start, end) = resolve_range(&range, self.size);
+        FralSlice {
+            fral: self.clone(),
+            offset: start,
+            len: end - start,
+        }
+    }
+    /// Get the elements in `range` as a list of their own. The front of the range is cut off in
+    /// O(log n), sharing every whole tree after the cut, and the leading trees which are also in
+    /// the canonical decomposition of the range's length are kept; only the rest is rebuilt.
+    ///
+    /// Unlike [`view`], the result does not hold onto the elements outside the range.
+    ///
+    /// Time: O(m + log n), for a range of length m
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is decreasing or extends past the end of the list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = (0..10).rev().collect();
+    /// let g = f.slice(2..8);
+    /// assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), vec![2, 3, 4, 5, 6, 7]);
+    /// assert!(f.slice(3..3).is_empty());
+    /// ```
+    ///
+    /// [`view`]: #method.view
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> Fral<T> {
+        let (start, end) = resolve_range(&range, self.size);
+        self.suffix(start).prefix(end - start)
+    }
+    /// Insert an element at the front of the list, sharing the element and the new tree with any
+    /// equal ones previously seen by the interner.
+    ///
+    /// Time: O(1), plus hashing the element
+    pub fn cons_interned<R>(&self, x: R, interner: &mut Interner<T>) -> Fral<T>
+    where
+        T: Eq + Hash,
+        R: AsArc<T>,
+    {
+        let x = interner.intern(x);
+        let pair = match self.pair().cons(x) {
+            Cons((size, tree), rest) => Cons((size, interner.tree(tree)), rest),
+            Nil => unreachable!(),
+        };
+        Fral {
+            size: 1 + self.size,
+            pair: Some(Arc::new(pair)),
+        }
+    }
+    /// Like [`FromIterator`], this is done with repeated `cons`, but every element and tree is
+    /// interned. Lists built from equal items by the same interner share all of their trees.
+    ///
+    /// [`FromIterator`]: https://doc.rust-lang.org/stable/std/iter/trait.FromIterator.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::{Fral, Interner};
+    /// let mut interner = Interner::new();
+    /// let f = Fral::from_iter_interned(0..100, &mut interner);
+    /// let g = Fral::from_iter_interned(0..100, &mut interner);
+    /// assert_eq!(f, g);
+    /// assert_eq!(interner.stats().elements, 100);
+    /// assert_eq!(interner.stats().misses, interner.stats().hits);
+    /// ```
+    pub fn from_iter_interned<I, R>(iter: I, interner: &mut Interner<T>) -> Fral<T>
+    where
+        T: Eq + Hash,
+        I: IntoIterator<Item = R>,
+        R: AsArc<T>,
+    {
+        let mut f = Fral::new();
+        for x in iter {
+            f = f.cons_interned(x, interner);
+        }
+        f
+    }
+    /// Get the first `n` elements, or the whole list if it is shorter. Leading trees are shared
+    /// where they also lead the new list, and the others are rebuilt.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = (0..5).rev().collect();
+    /// assert_eq!(f.take(2).iter().map(|x| *x).collect::<Vec<_>>(), vec![0, 1]);
+    /// assert_eq!(f.take(10), f);
+    /// ```
+    pub fn take(&self, n: usize) -> Fral<T> {
+        self.prefix(n.min(self.size))
+    }
+    /// Get the list without its first `n` elements, or an empty list if it is shorter. Whole
+    /// trees are skipped, and only the tree containing the cut is taken apart.
+    ///
+    /// Time: O(log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = (0..5).rev().collect();
+    /// assert_eq!(f.drop_front(2).iter().map(|x| *x).collect::<Vec<_>>(), vec![2, 3, 4]);
+    /// assert!(f.drop_front(10).is_empty());
+    /// ```
+    pub fn drop_front(&self, n: usize) -> Fral<T> {
+        self.suffix(n.min(self.size))
+    }
+    /// Get a list of exactly `n` elements, either the first `n` of this list or this list padded
+    /// at the back with copies of `x`. The padding is built as by [`repeat`], so all of it shares
+    /// one `Arc`, and the elements of this list are consed onto it.
+    ///
+    /// Time: O(len + log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 2, 3].into_iter().rev().collect();
+    /// assert_eq!(f.resize(5, 0).iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 2, 3, 0, 0]);
+    /// assert_eq!(f.resize(2, 0).iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 2]);
+    /// ```
+    ///
+    /// [`repeat`]: #method.repeat
+    pub fn resize<R: AsArc<T>>(&self, n: usize, x: R) -> Fral<T> {
+        if n <= self.size {
+            return self.prefix(n);
+        }
+        let items: Vec<_> = self.iter().collect();
+        Fral::repeat(x, n - self.size).extend_front(items)
+    }
+    /// Get this list padded at the back with copies of `x` to a length of at least `n`. Unlike
+    /// [`resize`], a longer list is returned as is.
+    ///
+    /// Time: O(len + log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 2, 3].into_iter().rev().collect();
+    /// assert_eq!(f.pad_to(4, 0).iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 2, 3, 0]);
+    /// assert_eq!(f.pad_to(2, 0), f);
+    /// ```
+    ///
+    /// [`resize`]: #method.resize
+    pub fn pad_to<R: AsArc<T>>(&self, n: usize, x: R) -> Fral<T> {
+        self.resize(n.max(self.size), x)
+    }
+    /// Get every `step`-th element, starting with the first. The elements are found with
+    /// [`get_many`], so subtrees holding none of them are skipped entirely.
+    ///
+    /// Time: O(k log n), for k selected elements
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = (0..10).rev().collect();
+    /// assert_eq!(f.step_by(4).iter().map(|x| *x).collect::<Vec<_>>(), vec![0, 4, 8]);
+    /// ```
+    ///
+    /// [`get_many`]: #method.get_many
+    pub fn step_by(&self, step: usize) -> Fral<T> {
+        assert!(step != 0, "step must be nonzero");
+        let indices: Vec<usize> = (0..self.size).step_by(step).collect();
+        let items: Vec<_> = self.get_many(&indices).into_iter().flatten().collect();
+        Fral::from_slice(&items)
+    }
+    /// Get the longest prefix of elements which all satisfy `pred`, in order.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 2, 5, 3].into_iter().rev().collect();
+    /// assert_eq!(f.take_while(|x| *x < 4).iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 2]);
+    /// ```
+    pub fn take_while<F>(&self, mut pred: F) -> Fral<T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let n = self.position(|x| !pred(x)).unwrap_or(self.size);
+        self.prefix(n)
+    }
+    /// Get the list without its longest prefix of elements which all satisfy `pred`. The rest of
+    /// the list is cut off as by [`drop_front`], so it shares every whole tree after the cut.
+    ///
+    /// Time: O(k + log n), for k skipped elements
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 2, 5, 3].into_iter().rev().collect();
+    /// assert_eq!(f.skip_while(|x| *x < 4).iter().map(|x| *x).collect::<Vec<_>>(), vec![5, 3]);
+    /// ```
+    ///
+    /// [`drop_front`]: #method.drop_front
+    pub fn skip_while<F>(&self, mut pred: F) -> Fral<T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let n = self.position(|x| !pred(x)).unwrap_or(self.size);
+        self.suffix(n)
+    }
+    /// Split the list into its longest prefix of elements which all satisfy `pred` and the rest.
+    /// This is [`take_while`] and [`skip_while`] together, with `pred` called only once per
+    /// element, and the rest shares every whole tree after the cut.
+    ///
+    /// Time: O(k + log n), for a prefix of length k
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let input: Fral<_> = "123abc".chars().rev().collect();
+    /// let (digits, rest) = input.span(|c| c.is_ascii_digit());
+    /// assert_eq!(digits.iter().map(|x| *x).collect::<String>(), "123");
+    /// assert_eq!(rest.iter().map(|x| *x).collect::<String>(), "abc");
+    /// ```
+    ///
+    /// [`take_while`]: #method.take_while
+    /// [`skip_while`]: #method.skip_while
+    pub fn span<F>(&self, mut pred: F) -> (Fral<T>, Fral<T>)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let n = self.position(|x| !pred(x)).unwrap_or(self.size);
+        (self.prefix(n), self.suffix(n))
+    }
+    /// Split the list just before the first element satisfying `pred`, or into the whole list
+    /// and an empty one if there is none. This is [`span`] with the predicate negated.
+    ///
+    /// Time: O(k + log n), for a split at index k
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let input: Fral<_> = "key=value".chars().rev().collect();
+    /// let (key, rest) = input.split_when(|c| *c == '=');
+    /// assert_eq!(key.iter().map(|x| *x).collect::<String>(), "key");
+    /// assert_eq!(rest.iter().map(|x| *x).collect::<String>(), "=value");
+    /// ```
+    ///
+    /// [`span`]: #method.span
+    pub fn split_when<F>(&self, mut pred: F) -> (Fral<T>, Fral<T>)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.span(|x| !pred(x))
+    }
+    /// Get the elements of this list followed by those of `other`. The elements of this list are
+    /// consed onto `other`, which is shared entirely.
+    ///
+    /// Time: O(n), for this list of length n
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 2].into_iter().rev().collect();
+    /// let g: Fral<_> = vec![3, 4].into_iter().rev().collect();
+    /// assert_eq!(f.append(&g).iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+    /// ```
+    pub fn append(&self, other: &Fral<T>) -> Fral<T> {
+        if other.is_empty() {
+            return self.clone();
+        }
+        let items: Vec<_> = self.iter().collect();
+        items
+            .into_iter()
+            .rev()
+            .fold(other.clone(), |f, x| f.cons(x))
+    }
+    /// Apply `f` to every element. The new list has exactly the same shape as this one, so the
+    /// trees are rebuilt directly, without any consing.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 2, 3].into_iter().rev().collect();
+    /// let g = f.map(|x| x.to_string());
+    /// assert_eq!(g.iter().map(|x| (*x).clone()).collect::<Vec<_>>(), vec!["1", "2", "3"]);
+    /// ```
+    pub fn map<U, F>(&self, f: F) -> Fral<U>
+    where
+        F: Fn(&T) -> U,
+    {
+        let pair = self.pair().map(&mut |x| Arc::new(f(x)));
+        Fral::from_pair(self.size, Arc::new(pair))
+    }
+    /// Apply the fallible `f` to every element in order, stopping at the first error. The new list
+    /// is built directly from the results, without any consing.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec!["1", "2", "3"].into_iter().rev().collect();
+    /// let g = f.try_map(|s| s.parse::<u8>()).unwrap();
+    /// assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// assert!(f.cons("x").try_map(|s| s.parse::<u8>()).is_err());
+    /// ```
+    pub fn try_map<U, E, F>(&self, f: F) -> Result<Fral<U>, E>
+    where
+        F: Fn(&T) -> Result<U, E>,
+    {
+        let mut items = Vec::with_capacity(self.size);
+        let mut error = None;
+        self.pair().all(&mut |x| match f(x) {
+            Ok(y) => {
+                items.push(Arc::new(y));
+                true
+            }
+            Err(e) => {
+                error = Some(e);
+                false
+            }
+        });
+        match error {
+            Some(e) => Err(e),
+            None => Ok(Fral::from_slice(&items)),
+        }
+    }
+    /// Apply `f` to the elements in order until it returns `None`, keeping the results before
+    /// that.
+    ///
+    /// Time: O(k), for k results
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let tokens: Fral<_> = vec!["1", "2", "+", "3"].into_iter().rev().collect();
+    /// let numbers = tokens.map_while(|s| s.parse::<u8>().ok());
+    /// assert_eq!(numbers.iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 2]);
+    /// ```
+    pub fn map_while<U, F>(&self, f: F) -> Fral<U>
+    where
+        F: Fn(&T) -> Option<U>,
+    {
+        let mut items = Vec::new();
+        self.pair().all(&mut |x| match f(x) {
+            Some(y) => {
+                items.push(Arc::new(y));
+                true
+            }
+            None => false,
+        });
+        Fral::from_slice(&items)
+    }
+    /// Get the running accumulations of `f` over the elements, starting from `init`, which is
+    /// not itself included. Like [`map`], the new list is built with the same shape.
+    ///
+    /// [`map`]: #method.map
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 2, 3, 4].into_iter().rev().collect();
+    /// let sums = f.scan(0, |acc, x| acc + x);
+    /// assert_eq!(sums.iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 3, 6, 10]);
+    /// ```
+    pub fn scan<A, F>(&self, init: A, f: F) -> Fral<A>
+    where
+        F: Fn(&A, &T) -> A,
+    {
+        let mut acc = Arc::new(init);
+        let pair = self.pair().map(&mut |x| {
+            acc = Arc::new(f(&acc, x));
+            acc.clone()
+        });
+        Fral::from_pair(self.size, Arc::new(pair))
+    }
+    /// Get the elements for which `pred` returns true, in order. The elements themselves are
+    /// shared with this list.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = (1..=6).rev().collect();
+    /// let evens = f.filter(|x| x % 2 == 0);
+    /// assert_eq!(evens.iter().map(|x| *x).collect::<Vec<_>>(), vec![2, 4, 6]);
+    /// ```
+    pub fn filter<F>(&self, pred: F) -> Fral<T>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let mut items = Vec::new();
+        self.pair().all(&mut |x| {
+            if pred(x) {
+                items.push(x.clone());
+            }
+            true
+        });
+        Fral::from_slice(&items)
+    }
+    /// Split the elements into those for which `pred` returns true and those for which it
+    /// returns false, both in order, in a single traversal.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = (1..=6).rev().collect();
+    /// let (evens, odds) = f.partition(|x| x % 2 == 0);
+    /// assert_eq!(evens.iter().map(|x| *x).collect::<Vec<_>>(), vec![2, 4, 6]);
+    /// assert_eq!(odds.iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 3, 5]);
+    /// ```
+    pub fn partition<F>(&self, pred: F) -> (Fral<T>, Fral<T>)
+    where
+        F: Fn(&T) -> bool,
+    {
+        let (mut matching, mut rest) = (Vec::new(), Vec::new());
+        self.pair().all(&mut |x| {
+            if pred(x) {
+                matching.push(x.clone());
+            } else {
+                rest.push(x.clone());
+            }
+            true
+        });
+        (Fral::from_slice(&matching), Fral::from_slice(&rest))
+    }
+    /// Collapse runs of consecutive equal elements into their first element. Everything after
+    /// the last removed element is shared with this list.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 1, 2, 1, 3, 3].into_iter().rev().collect();
+    /// assert_eq!(f.dedup().iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 2, 1, 3]);
+    /// ```
+    pub fn dedup(&self) -> Fral<T>
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b)
+    }
+    /// Remove each element for which `same` returns true when given the last element kept before
+    /// it and the element itself. Everything after the last removed element is shared with this
+    /// list.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec!["a", "A", "b", "B", "c"].into_iter().rev().collect();
+    /// let g = f.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    /// assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), vec!["a", "b", "c"]);
+    /// ```
+    pub fn dedup_by<F>(&self, same: F) -> Fral<T>
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        let mut kept: Vec<Arc<T>> = Vec::new();
+        // the position just after the last removed element, and how many were kept before it.
+        let (mut end, mut kept_before_end) = (0, 0);
+        let mut index = 0;
+        self.pair().all(&mut |x| {
+            index += 1;
+            match kept.last() {
+                Some(prev) if same(prev, x) => {
+                    end = index;
+                    kept_before_end = kept.len();
+                }
+                _ => kept.push(x.clone()),
+            }
+            true
+        });
+        if end == 0 {
+            return self.clone();
+        }
+        kept.truncate(kept_before_end);
+        kept.into_iter()
+            .rev()
+            .fold(self.suffix(end), |f, x| f.cons(x))
+    }
+    /// Remove every element equal to an earlier one, wherever it is, keeping the first
+    /// occurrences in order. The elements are tracked by reference, and like [`dedup`], the
+    /// suffix after the last removed element is shared.
+    ///
+    /// Time: O(n) expected
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![3, 1, 3, 2, 1, 4].into_iter().rev().collect();
+    /// assert_eq!(f.unique().iter().map(|x| *x).collect::<Vec<_>>(), vec![3, 1, 2, 4]);
+    /// ```
+    ///
+    /// [`dedup`]: #method.dedup
+    pub fn unique(&self) -> Fral<T>
+    where
+        T: Hash + Eq,
+    {
+        let mut seen: HashSet<&T> = HashSet::new();
+        let mut kept: Vec<Arc<T>> = Vec::new();
+        // the position just after the last removed element, and how many were kept before it.
+        let (mut end, mut kept_before_end) = (0, 0);
+        let mut index = 0;
+        self.pair().all(&mut |x| {
+            index += 1;
+            if seen.insert(x) {
+                kept.push(x.clone());
+            } else {
+                end = index;
+                kept_before_end = kept.len();
+            }
+            true
+        });
+        if end == 0 {
+            return self.clone();
+        }
+        kept.truncate(kept_before_end);
+        self.suffix(end).extend_front(kept)
+    }
+    /// Split the list into runs of consecutive elements for which `f` gives equal keys, in
+    /// order. The last run shares its structure with this list.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 3, 2, 4, 6, 5].into_iter().rev().collect();
+    /// let groups = f.group_by(|x| x % 2);
+    /// let groups: Vec<Vec<_>> = groups
+    ///     .iter()
+    ///     .map(|g| g.iter().map(|x| *x).collect())
+    ///     .collect();
+    /// assert_eq!(groups, vec![vec![1, 3], vec![2, 4, 6], vec![5]]);
+    /// ```
+    pub fn group_by<K, F>(&self, mut f: F) -> Fral<Fral<T>>
+    where
+        K: PartialEq,
+        F: FnMut(&T) -> K,
+    {
+        if self.is_empty() {
+            return Fral::new();
+        }
+        let mut starts = Vec::new();
+        let mut prev = None;
+        let mut index = 0;
+        self.pair().all(&mut |x| {
+            let key = f(x);
+            if prev.as_ref().is_some_and(|prev| *prev != key) {
+                starts.push(index);
+            }
+            prev = Some(key);
+            index += 1;
+            true
+        });
+        self.split_at_many(&starts).into_iter().rev().collect()
+    }
+    /// Apply `f` to every element, keeping the results which are not `None`, in order.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec!["1", "x", "3"].into_iter().rev().collect();
+    /// let g = f.filter_map(|s| s.parse::<u8>().ok());
+    /// assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 3]);
+    /// ```
+    pub fn filter_map<U, F>(&self, f: F) -> Fral<U>
+    where
+        F: Fn(&T) -> Option<U>,
+    {
+        let mut items = Vec::new();
+        self.pair().all(&mut |x| {
+            items.extend(f(x).map(Arc::new));
+            true
+        });
+        Fral::from_slice(&items)
+    }
+    /// Apply `f` to every element and concatenate the results, in order.
+    ///
+    /// Time: O(n + m), for m elements in the result
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 2, 3].into_iter().rev().collect();
+    /// let g = f.flat_map(|&x| vec![x; x]);
+    /// assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 2, 2, 3, 3, 3]);
+    /// ```
+    pub fn flat_map<U, I, F>(&self, f: F) -> Fral<U>
+    where
+        I: IntoIterator<Item = U>,
+        F: Fn(&T) -> I,
+    {
+        let mut items = Vec::new();
+        self.pair().all(&mut |x| {
+            items.extend(f(x).into_iter().map(Arc::new));
+            true
+        });
+        Fral::from_slice(&items)
+    }
+    /// Pair up the elements of this list and `other`, in order, stopping at the end of the
+    /// shorter list.
+    ///
+    /// Time: O(min(n, m))
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// # use std::sync::Arc;
+    /// let f: Fral<_> = vec!["a", "b", "c"].into_iter().rev().collect();
+    /// let g: Fral<_> = vec![1, 2].into_iter().rev().collect();
+    /// let zipped = f.zip(&g);
+    /// assert_eq!(zipped.len(), 2);
+    /// assert_eq!(zipped.get(1), Some(Arc::new((Arc::new("b"), Arc::new(2)))));
+    /// ```
+    pub fn zip<U: ?Sized>(&self, other: &Fral<U>) -> Fral<(Arc<T>, Arc<U>)> {
+        self.zip_arcs(other, |x, y| Arc::new((x.clone(), y.clone())))
+    }
+    /// Combine the elements of this list and `other` with `f`, in order, stopping at the end of
+    /// the shorter list.
+    ///
+    /// Time: O(min(n, m))
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 2, 3].into_iter().rev().collect();
+    /// let g: Fral<_> = vec![10, 20, 30, 40].into_iter().rev().collect();
+    /// let sums = f.zip_with(&g, |x, y| x + y);
+    /// assert_eq!(sums.iter().map(|x| *x).collect::<Vec<_>>(), vec![11, 22, 33]);
+    /// ```
+    pub fn zip_with<U: ?Sized, V, F>(&self, other: &Fral<U>, f: F) -> Fral<V>
+    where
+        F: Fn(&T, &U) -> V,
+    {
+        self.zip_arcs(other, |x, y| Arc::new(f(x, y)))
+    }
+    fn zip_arcs<U: ?Sized, V: ?Sized, F>(&self, other: &Fral<U>, mut f: F) -> Fral<V>
+    where
+        F: FnMut(&Arc<T>, &Arc<U>) -> Arc<V>,
+    {
+        let len = self.size.min(other.size);
+        let mut others = other.iter();
+        let mut items = Vec::with_capacity(len);
+        if len > 0 {
+            self.pair().all(&mut |x| {
+                // `others` is at least as long as the items visited.
+                items.push(f(x, &others.next().unwrap()));
+                items.len() < len
+            });
+        }
+        Fral::from_slice(&items)
+    }
+    /// Get the elements with `separator` between each adjacent pair. Every separator is the same
+    /// allocation.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec!["a", "b", "c"].into_iter().rev().collect();
+    /// let g = f.intersperse(",");
+    /// assert_eq!(g.iter().map(|x| *x).collect::<String>(), "a,b,c");
+    /// ```
+    pub fn intersperse<R: AsArc<T>>(&self, separator: R) -> Fral<T> {
+        let items: Vec<_> = self.iter_intersperse(separator).collect();
+        Fral::from_slice(&items)
+    }
+    /// Iterate over the elements with `separator` between each adjacent pair, without building
+    /// a new list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 2, 3].into_iter().rev().collect();
+    /// let items: Vec<_> = f.iter_intersperse(0).map(|x| *x).collect();
+    /// assert_eq!(items, vec![1, 0, 2, 0, 3]);
+    /// ```
+    pub fn iter_intersperse<R: AsArc<T>>(&self, separator: R) -> Intersperse<T> {
+        Intersperse {
+            iter: self.iter().peekable(),
+            separator: separator.as_arc(),
+            separator_next: false,
+        }
+    }
+    /// Get the elements in reverse order. Each element is consed onto the result as it is
+    /// visited, without any intermediate buffer.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 2, 3].into_iter().rev().collect();
+    /// assert_eq!(f.reverse().iter().map(|x| *x).collect::<Vec<_>>(), vec![3, 2, 1]);
+    /// ```
+    pub fn reverse(&self) -> Fral<T> {
+        let mut reversed = Fral::new();
+        self.pair().all(&mut |x| {
+            reversed = reversed.cons(x.clone());
+            true
+        });
+        reversed
+    }
+    /// Split the list into its first `index` elements and the rest. The second list shares every
+    /// whole tree after the cut, and only the tree containing the cut is taken apart.
+    ///
+    /// Time: O(index + log n)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = (0..5).rev().collect();
+    /// let (front, back) = f.split_at(2);
+    /// assert_eq!(front.iter().map(|x| *x).collect::<Vec<_>>(), vec![0, 1]);
+    /// assert_eq!(back.iter().map(|x| *x).collect::<Vec<_>>(), vec![2, 3, 4]);
+    /// ```
+    pub fn split_at(&self, index: usize) -> (Fral<T>, Fral<T>) {
+        assert!(
+            index <= self.size,
+            "split index {} is out of bounds for length {}",
+            index,
+            self.size
+        );
+        (self.prefix(index), self.suffix(index))
+    }
+    /// Split the list into its first `index` elements and the rest, or return an
+    /// [`IndexOutOfBounds`] error if `index` is greater than the length.
+    ///
+    /// Time: O(index + log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::{Fral, IndexOutOfBounds};
+    /// let f: Fral<_> = (0..5).rev().collect();
+    /// assert!(f.try_split_at(5).is_ok());
+    /// assert_eq!(f.try_split_at(6).unwrap_err(), IndexOutOfBounds { index: 6, len: 5 });
+    /// ```
+    ///
+    /// [`IndexOutOfBounds`]: struct.IndexOutOfBounds.html
+    pub fn try_split_at(&self, index: usize) -> Result<(Fral<T>, Fral<T>), IndexOutOfBounds> {
+        if index > self.size {
+            return Err(self.out_of_bounds(index));
+        }
+        Ok((self.prefix(index), self.suffix(index)))
+    }
+    /// Split the list at each of the sorted `indices`, returning the `indices.len() + 1` segments
+    /// in order. The elements before the last index are visited once, and the last segment shares
+    /// its structure with this list.
+    ///
+    /// Time: O(m + log n), where m is the last index
+    ///
+    /// # Panics
+    ///
+    /// Panics if the indices are not sorted or if any index is greater than the length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = (0..10).rev().collect();
+    /// let segments = f.split_at_many(&[2, 2, 5]);
+    /// let segments: Vec<Vec<_>> = segments
+    ///     .iter()
+    ///     .map(|s| s.iter().map(|x| *x).collect())
+    ///     .collect();
+    /// assert_eq!(
+    ///     segments,
+    ///     vec![vec![0, 1], vec![], vec![2, 3, 4], vec![5, 6, 7, 8, 9]]
+    /// );
+    /// ```
+    pub fn split_at_many(&self, indices: &[usize]) -> Vec<Fral<T>> {
+        assert!(
+            indices.windows(2).all(|w| w[0] <= w[1]),
+            "split indices {:?} are not sorted",
+            indices
+        );
+        let last = indices.last().cloned().unwrap_or(0);
+        assert!(
+            last <= self.size,
+            "split index {} is out of bounds for length {}",
+            last,
+            self.size
+        );
+        let mut items = Vec::with_capacity(last);
+        if last > 0 {
+            self.pair().all(&mut |x| {
+                items.push(x.clone());
+                items.len() < last
+            });
+        }
+        let mut segments = Vec::with_capacity(indices.len() + 1);
+        let mut start = 0;
+        for &i in indices {
+            segments.push(Fral::from_slice(&items[start..i]));
+            start = i;
+        }
+        segments.push(self.suffix(last));
+        segments
+    }
+    /// Replace the elements in `range` with those of `replacement`. Everything after the range is
+    /// shared with this list, and the elements before it are consed back on.
+    ///
+    /// Time: O(a + m + log n), for a range starting at a and a replacement of length m
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is decreasing or extends past the end of the list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = "hello world".chars().rev().collect();
+    /// let g = f.splice(0..5, "goodbye".chars());
+    /// assert_eq!(g.iter().map(|x| *x).collect::<String>(), "goodbye world");
+    /// assert_eq!(f.splice(5.., None::<char>).len(), 5);
+    /// ```
+    pub fn splice<Rg, I, R>(&self, range: Rg, replacement: I) -> Fral<T>
+    where
+        Rg: RangeBounds<usize>,
+        I: IntoIterator<Item = R>,
+        R: AsArc<T>,
+    {
+        let (start, end) = resolve_range(&range, self.size);
+        let replacement: Vec<_> = replacement.into_iter().map(AsArc::as_arc).collect();
+        let mut items = Vec::with_capacity(start + replacement.len());
+        if start > 0 {
+            self.pair().all(&mut |x| {
+                items.push(x.clone());
+                items.len() < start
+            });
+        }
+        items.extend(replacement);
+        items
+            .into_iter()
+            .rev()
+            .fold(self.suffix(end), |f, x| f.cons(x))
+    }
+    /// Rotate the list so that its first `n` elements move to the back, with the element at
+    /// index `n` becoming the first. The list is [`split_at`] `n`, and the back is [`append`]ed
+    /// onto the front, which is shared entirely.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = (0..5).rev().collect();
+    /// assert_eq!(f.rotate_left(2).iter().map(|x| *x).collect::<Vec<_>>(), vec![2, 3, 4, 0, 1]);
+    /// ```
+    ///
+    /// [`split_at`]: #method.split_at
+    /// [`append`]: #method.append
+    pub fn rotate_left(&self, n: usize) -> Fral<T> {
+        let (front, back) = self.split_at(n);
+        back.append(&front)
+    }
+    /// Rotate the list so that its last `n` elements move to the front. This is
+    /// [`rotate_left`] by the length minus `n`.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = (0..5).rev().collect();
+    /// assert_eq!(f.rotate_right(2).iter().map(|x| *x).collect::<Vec<_>>(), vec![3, 4, 0, 1, 2]);
+    /// ```
+    ///
+    /// [`rotate_left`]: #method.rotate_left
+    pub fn rotate_right(&self, n: usize) -> Fral<T> {
+        assert!(
+            n <= self.size,
+            "rotation {} is out of bounds for length {}",
+            n,
+            self.size
+        );
+        self.rotate_left(self.size - n)
+    }
+    /// Get the number of strong references to the root of this list, i.e. how many lists
+    /// (including this one) share this exact version. Empty lists have no root, so this is zero
+    /// for them.
+    ///
+    /// As with [`sharing_report`], other threads may change this concurrently.
+    ///
+    /// Time: O(1)
+    ///
+    /// [`sharing_report`]: #method.sharing_report
+    pub fn root_strong_count(&self) -> usize {
+        self.pair.as_ref().map_or(0, Arc::strong_count)
+    }
+    /// Get the largest number of strong references to any element of the list, or `None` if the
+    /// list is empty. Counts above one come from elements held outside of the list, and also
+    /// from other versions: path-copying operations such as [`update`] build new tree nodes that
+    /// hold the same elements as the nodes they replace.
+    ///
+    /// As with [`sharing_report`], other threads may change this concurrently.
+    ///
+    /// Time: O(n)
+    ///
+    /// [`sharing_report`]: #method.sharing_report
+    /// [`update`]: #method.update
+    pub fn max_element_strong_count(&self) -> Option<usize> {
+        let mut max = None;
+        self.pair().all(&mut |x| {
+            max = max.max(Some(Arc::strong_count(x)));
+            true
+        });
+        max
+    }
+    /// Returns true iff nothing in the list is shared: no spine cell, tree node or element has
+    /// another strong reference. Then [`get_mut_unique`] succeeds at every index, and nothing
+    /// would be released early by dropping other lists.
+    ///
+    /// As with [`sharing_report`], other threads may change this concurrently.
+    ///
+    /// Time: O(n), and O(1) if the root is shared
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = (0..10).collect();
+    /// assert!(f.is_unique());
+    /// let g = f.tail().unwrap();
+    /// assert!(!f.is_unique());
+    /// drop(g);
+    /// assert!(f.is_unique());
+    /// ```
+    ///
+    /// [`get_mut_unique`]: #method.get_mut_unique
+    /// [`sharing_report`]: #method.sharing_report
+    pub fn is_unique(&self) -> bool {
+        let mut pair = match self.pair {
+            Some(ref pair) => pair,
+            None => return true,
+        };
+        loop {
+            match **pair {
+                Nil => return true,
+                Cons((_, ref tree), ref rest) => {
+                    if Arc::strong_count(pair) > 1 || !Tree::is_unique(tree) {
+                        return false;
+                    }
+                    pair = rest;
+                }
+            }
+        }
+    }
+    /// Get counts of the nodes and elements of the list which are shared with other lists. A
+    /// node is shared if it has another strong reference, or if any node above it does.
+    ///
+    /// Other threads may create or drop references concurrently, so the result is only a
+    /// snapshot and should be used for diagnostics rather than synchronization.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = (0..3).collect();
+    /// let g = f.cons(3);
+    /// let report = g.sharing_report();
+    /// // g has a spine of two cells and a new leaf, and shares f's tree of three nodes.
+    /// assert_eq!((report.nodes, report.shared_nodes, report.shared_elements), (6, 3, 3));
+    /// assert_eq!(report.unique_nodes(), 3);
+    /// ```
+    pub fn sharing_report(&self) -> SharingReport {
+        let mut report = SharingReport::default();
+        let mut shared = false;
+        let mut pair = match self.pair {
+            Some(ref pair) => pair,
+            None => return report,
+        };
+        while let Cons((_, ref tree), ref rest) = **pair {
+            shared = shared || Arc::strong_count(pair) > 1;
+            report.nodes += 1;
+            if shared {
+                report.shared_nodes += 1;
+            }
+            Tree::report(tree, shared, &mut report);
+            pair = rest;
+        }
+        report
+    }
+    /// Returns a list with the same elements in an order chosen by `rng`. The elements are
+    /// shared with this list rather than cloned.
+    ///
+    /// Requires the `rand` feature.
+    ///
+    /// Time: O(n)
+    #[cfg(feature = "rand")]
+    pub fn shuffle<R: Rng>(&self, rng: &mut R) -> Fral<T> {
+        let mut items: Vec<_> = self.iter().collect();
+        rng.shuffle(&mut items);
+        items.into_iter().collect()
+    }
+    /// Returns an element chosen uniformly at random by `rng`, or `None` if the list is empty.
+    ///
+    /// Requires the `rand` feature.
+    ///
+    /// Time: O(log n)
+    #[cfg(feature = "rand")]
+    pub fn choose<R: Rng>(&self, rng: &mut R) -> Option<Arc<T>> {
+        if self.is_empty() {
+            None
+        } else {
+            self.get(rng.gen_range(0, self.size))
+        }
+    }
+    /// Returns `k` distinct elements (by position) chosen uniformly at random by `rng`, in random
+    /// order. If `k` is at least the length of the list, every element is returned.
+    ///
+    /// Requires the `rand` feature.
+    ///
+    /// Time: O(k log n)
+    #[cfg(feature = "rand")]
+    pub fn sample<R: Rng>(&self, rng: &mut R, k: usize) -> Vec<Arc<T>> {
+        let k = k.min(self.size);
+        seq::sample_indices(rng, self.size, k)
+            .into_iter()
+            .filter_map(|i| self.get(i))
+            .collect()
+    }
+    /// Returns true iff the spine is in skew-binary form: tree sizes are of the form 2^k - 1,
+    /// strictly increasing except that the first two may be equal, and sum to the length.
+    #[cfg(any(test, kani))]
+    pub(crate) fn is_well_formed(&self) -> bool {
+        let mut total = 0;
+        let mut prev = 0;
+        let mut pair = self.pair();
+        let mut i = 0;
+        while let Cons((size, ref tree), ref rest) = *pair {
+            if !(size + 1).is_power_of_two()
+                || !tree.is_well_formed(size)
+                || (i == 1 && size < prev)
+                || (i > 1 && size <= prev)
+            {
+                return false;
+            }
+            total += size;
+            prev = size;
+            pair = rest;
+            i += 1;
+        }
+        total == self.size
+    }
+}
+/// Helpers for heterogeneous lists.
+///
+/// # Examples
+///
+/// ```
+/// # use fral::Fral;
+/// # use std::any::Any;
+/// # use std::sync::Arc;
+/// let f: Fral<dyn Any + Send + Sync> = Fral::new().cons_any(1u8).cons_any("two").cons_any(3u8);
+/// assert_eq!(f.get_downcast::<&str>(1), Some(Arc::new("two")));
+/// assert_eq!(f.get_downcast::<u8>(1), None);
+/// assert_eq!(f.iter_downcast::<u8>().map(|x| *x).collect::<Vec<_>>(), vec![3, 1]);
+/// ```
+impl Fral<dyn Any + Send + Sync> {
+    /// Insert an element of any type at the front of the list.
+    ///
+    /// Time: O(1)
+    pub fn cons_any<U: Any + Send + Sync>(&self, x: U) -> Fral<dyn Any + Send + Sync> {
+        let x: Arc<dyn Any + Send + Sync> = Arc::new(x);
+        self.cons(x)
+    }
+    /// Returns an element, or `None` if it is out of bounds or not of type `U`.
+    ///
+    /// Time: O(log n)
+    pub fn get_downcast<U: Any + Send + Sync>(&self, index: usize) -> Option<Arc<U>> {
+        self.get(index).and_then(|x| x.downcast().ok())
+    }
+    /// Iterate over the elements of type `U`, skipping all others.
+    pub fn iter_downcast<U: Any + Send + Sync>(&self) -> impl Iterator<Item = Arc<U>> {
+        self.iter().filter_map(|x| x.downcast().ok())
+    }
+}
+
+impl<A: Clone, B: Clone> Fral<(A, B)> {
+    /// Split a list of pairs into a list of the first components and a list of the second
+    /// components, in order, in a single traversal.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![(1, 'a'), (2, 'b')].into_iter().rev().collect();
+    /// let (numbers, letters) = f.unzip();
+    /// assert_eq!(numbers.iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 2]);
+    /// assert_eq!(letters.iter().map(|x| *x).collect::<Vec<_>>(), vec!['a', 'b']);
+    /// ```
+    pub fn unzip(&self) -> (Fral<A>, Fral<B>) {
+        let mut firsts = Vec::with_capacity(self.size);
+        let mut seconds = Vec::with_capacity(self.size);
+        self.pair().all(&mut |x| {
+            firsts.push(Arc::new(x.0.clone()));
+            seconds.push(Arc::new(x.1.clone()));
+            true
+        });
+        (Fral::from_slice(&firsts), Fral::from_slice(&seconds))
+    }
+}
+
+impl<T: ?Sized> Fral<Fral<T>> {
+    /// Concatenate the lists, in order. The elements of all but the last list are consed onto
+    /// the last list, which is shared entirely.
+    ///
+    /// Time: O(m + k), for m elements before the last list and k lists
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<_> = vec![1, 2].into_iter().rev().collect();
+    /// let g: Fral<_> = vec![3].into_iter().collect();
+    /// let nested: Fral<_> = vec![f, Fral::new(), g].into_iter().rev().collect();
+    /// assert_eq!(nested.flatten().iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// ```
+    pub fn flatten(&self) -> Fral<T> {
+        let last = match self.last() {
+            Some(last) => last,
+            None => return Fral::new(),
+        };
+        let mut items = Vec::new();
+        let mut lists = self.size - 1;
+        if lists > 0 {
+            self.pair().all(&mut |list| {
+                items.extend(list.iter());
+                lists -= 1;
+                lists > 0
+            });
+        }
+        items
+            .into_iter()
+            .rev()
+            .fold((*last).clone(), |f, x| f.cons(x))
+    }
+    /// Concatenate the lists, in order, with the elements of `separator` between each pair of
+    /// them. The elements of all but the last list, and of the separators, are consed onto the
+    /// last list, which is shared entirely.
+    ///
+    /// Time: O(m + k s), for m elements before the last list, k lists, and a separator of length s
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let words: Fral<Fral<char>> = vec!["ab", "", "c"]
+    ///     .into_iter()
+    ///     .rev()
+    ///     .map(|w| w.chars().rev().collect::<Fral<_>>())
+    ///     .collect();
+    /// let sep: Fral<_> = ", ".chars().rev().collect();
+    /// assert_eq!(words.join(&sep).iter().map(|x| *x).collect::<String>(), "ab, , c");
+    /// ```
+    pub fn join(&self, separator: &Fral<T>) -> Fral<T> {
+        let last = match self.last() {
+            Some(last) => last,
+            None => return Fral::new(),
+        };
+        let mut items = Vec::new();
+        let mut lists = self.size - 1;
+        if lists > 0 {
+            self.pair().all(&mut |list| {
+                items.extend(list.iter());
+                items.extend(separator.iter());
+                lists -= 1;
+                lists > 0
+            });
+        }
+        last.extend_front(items)
+    }
+}
+/// The number of bytes gathered before each scan in the byte search methods.
+#[cfg(feature = "memchr")]
+const SCAN_BLOCK: usize = 4096;
+
+#[cfg(feature = "memchr")]
+impl Fral<u8> {
+    /// Get the index of the first occurrence of `byte`, or `None` if it is not in the list.
+    ///
+    /// The bytes are gathered into blocks which are scanned with `memchr`, rather than compared
+    /// one at a time.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<u8> = b"hello, world".iter().cloned().rev().collect();
+    /// assert_eq!(f.find_byte(b','), Some(5));
+    /// assert_eq!(f.find_byte(b'!'), None);
+    /// ```
+    pub fn find_byte(&self, byte: u8) -> Option<usize> {
+        self.scan_blocks(0, |block| memchr::memchr(byte, block))
+    }
+    /// Get the index of the first occurrence of `needle` as a contiguous run of the list, or
+    /// `None` if it does not occur. An empty needle is found at index 0.
+    ///
+    /// Time: O(n + m), for a needle of length m
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fral::Fral;
+    /// let f: Fral<u8> = b"hello, world".iter().cloned().rev().collect();
+    /// assert_eq!(f.find_subsequence(b"world"), Some(7));
+    /// assert_eq!(f.find_subsequence(b"word"), None);
+    /// ```
+    pub fn find_subsequence(&self, needle: &[u8]) -> Option<usize> {
+        if needle.is_empty() {
+            return Some(0);
+        }
+        if needle.len() > self.size {
+            return None;
+        }
+        let finder = memchr::memmem::Finder::new(needle);
+        self.scan_blocks(needle.len() - 1, |block| finder.find(block))
+    }
+    /// Run `find` over consecutive blocks of the list, each starting with the last `overlap`
+    /// bytes of the previous block, and return the first match.
+    fn scan_blocks<F>(&self, overlap: usize, mut find: F) -> Option<usize>
+    where
+        F: FnMut(&[u8]) -> Option<usize>,
+    {
+        let capacity = SCAN_BLOCK.max(2 * overlap);
+        let mut block = Vec::with_capacity(capacity);
+        // the index in the list of the start of the block.
+        let mut start = 0;
+        let mut found = None;
+        self.pair().all(&mut |x| {
+            block.push(**x);
+            if block.len() == capacity {
+                if let Some(i) = find(&block) {
+                    found = Some(start + i);
+                    return false;
+                }
+                block.drain(..capacity - overlap);
+                start += capacity - overlap;
+            }
+            true
+        });
+        found.or_else(|| find(&block).map(|i| start + i))
+    }
+}
+
+impl<T: ?Sized> Clone for Fral<T> {
+    fn clone(&self) -> Fral<T> {
+        Fral {
+            size: self.size,
+            pair: self.pair.clone(),
+        }
+    }
+}
+/// Lists are equal when their elements are pairwise equal, so lists of different element types
+/// can be compared. Elements are compared even for two handles to the same version, so that
+/// element types such as `f64` keep their own notion of equality; use [`ptr_eq`] to check for
+/// the same version in O(1).
+///
+/// # Examples
+///
+/// ```
+/// # use fral::Fral;
+/// let owned: Fral<String> = vec!["a".to_string(), "b".to_string()].into_iter().collect();
+/// let borrowed: Fral<&str> = vec!["a", "b"].into_iter().collect();
+/// assert_eq!(owned, borrowed);
+/// ```
+///
+/// [`ptr_eq`]: struct.Fral.html#method.ptr_eq
+impl<T, U> PartialEq<Fral<U>> for Fral<T>
+where
+    T: ?Sized + PartialEq<U>,
+    U: ?Sized,
+{
+    fn eq(&self, other: &Fral<U>) -> bool {
+        // the shape is determined by the length, so only the elements need comparing.
+        self.size == other.size && self.iter().zip(other.iter()).all(|(x, y)| *x == *y)
+    }
+}
+impl<T: ?Sized + Eq> Eq for Fral<T> {}
+/// Concatenation, with [`append`].
+///
+/// [`append`]: struct.Fral.html#method.append
+///
+/// # Examples
+///
+/// ```
+/// # use fral::Fral;
+/// let f: Fral<_> = vec![1, 2].into_iter().rev().collect();
+/// let g = &f + &f;
+/// assert_eq!((g + f).iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 2, 1, 2, 1, 2]);
+/// ```
+impl<'a, T: ?Sized> Add for &'a Fral<T> {
+    type Output = Fral<T>;
+    fn add(self, other: &'a Fral<T>) -> Fral<T> {
+        self.append(other)
+    }
+}
+impl<'a, T: ?Sized> Add<&'a Fral<T>> for Fral<T> {
+    type Output = Fral<T>;
+    fn add(self, other: &'a Fral<T>) -> Fral<T> {
+        self.append(other)
+    }
+}
+impl<T: ?Sized> Add for Fral<T> {
+    type Output = Fral<T>;
+    fn add(self, other: Fral<T>) -> Fral<T> {
+        self.append(&other)
+    }
+}
+impl<T: ?Sized> Default for Fral<T> {
+    fn default() -> Fral<T> {
+        Fral::new()
+    }
+}
+/// Indexing, with [`get_ref`].
+///
+/// # Panics
+///
+/// Panics if the index is out of bounds.
+///
+/// # Examples
+///
+/// ```
+/// # use fral::Fral;
+/// let f: Fral<_> = vec![7, 0, 17].into_iter().rev().collect();
+/// assert_eq!(f[2], 17);
+/// ```
+///
+/// [`get_ref`]: struct.Fral.html#method.get_ref
+impl<T: ?Sized> Index<usize> for Fral<T> {
+    type Output = T;
+    fn index(&self, index: usize) -> &T {
+        match self.get_ref(index) {
+            Some(x) => x,
+            None => panic!("{}", self.out_of_bounds(index)),
+        }
+    }
+}
+impl<T: ?Sized> IntoIterator for Fral<T> {
+    type Item = Arc<T>;
+    type IntoIter = Iter<T>;
+    fn into_iter(self) -> Iter<T> {
+        Iter::new(self)
+    }
+}
+/// Construct a list holding the array's elements in the written order, unlike collecting.
+///
+/// Time: O(n)
+///
+/// # Examples
+///
+/// ```
+/// # use fral::Fral;
+/// let f = Fral::from([1, 2, 3]);
+/// assert_eq!(f.iter().map(|x| *x).collect::<Vec<_>>(), vec![1, 2, 3]);
+/// ```
+impl<T, const N: usize> From<[T; N]> for Fral<T> {
+    fn from(items: [T; N]) -> Fral<T> {
+        let items: Vec<_> = IntoIterator::into_iter(items).map(Arc::new).collect();
+        Fral::from_slice(&items)
+    }
+}
+/// This is done with repeated `cons`, so you may intend to reverse your iterator first.
+///
+/// # Examples
+///
+/// ```
+/// # use fral::Fral;
+/// # use std::sync::Arc;
+/// let items = vec![1, 2, 3];
+/// let f: Fral<_> = items.into_iter().collect();
+///
+/// // the first item in f is the last item of the iterator
+/// assert_eq!(f.get(0), Some(Arc::new(3)));
+/// ```
+impl<T: ?Sized, R: AsArc<T>> FromIterator<R> for Fral<T> {
+    fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> Fral<T> {
+        let mut f = Fral::new();
+        for x in iter {
+            f = f.cons(x);
+        }
+        f
+    }
+}
+
+/// Get the start and end of a range within a list of the given length, panicking if it is out of
+/// bounds.
+fn resolve_range<R: RangeBounds<usize>>(range: &R, len: usize) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(&i) => i,
+        Bound::Excluded(&i) => i + 1,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&i) => i + 1,
+        Bound::Excluded(&i) => i,
+        Bound::Unbounded => len,
+    };
+    assert!(
+        start <= end,
+        "range starts at {} but ends at {}",
+        start,
+        end
+    );
+    assert!(
+        end <= len,
+        "range end {} is out of bounds for length {}",
+        end,
+        len
+    );
+    (start, end)
+}
+
+/// A view of a contiguous range of a [`Fral`], created with [`view`]. It holds onto the whole
+/// list, so creating and subslicing views never copies any structure.
+///
+/// [`Fral`]: struct.Fral.html
+/// [`view`]: struct.Fral.html#method.view
+pub struct FralSlice<T: ?Sized> {
+    fral: Fral<T>,
+    offset: usize,
+    len: usize,
+}
+impl<T: ?Sized> FralSlice<T> {
+    /// Returns a reference to an element of the view, or `None` if it is out of bounds.
+    ///
+    /// Time: O(log n)
+    pub fn get(&self, index: usize) -> Option<Arc<T>> {
+        if index < self.len {
+            self.fral.get(self.offset + index)
+        } else {
+            None
+        }
+    }
+    /// Returns true iff the view contains no elements.
+    ///
+    /// Time: O(1)
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Get the number of items in the view.
+    ///
+    /// Time: O(1)
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Get a view of a range of this view, relative to its start.
+    ///
+    /// Time: O(1)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is decreasing or extends past the end of the view.
+    pub fn view<R: RangeBounds<usize>>(&self, range: R) -> FralSlice<T> {
+        let (start, end) = resolve_range(&range, self.len);
+        FralSlice {
+            fral: self.fral.clone(),
+            offset: self.offset + start,
+            len: end - start,
+        }
+    }
+    /// Iterate over the elements of the view.
+    ///
+    /// Time: O(log n) to start, then O(1) per element
+    pub fn iter(&self) -> Take<Iter<T>> {
+        self.fral.suffix(self.offset).iter().take(self.len)
+    }
+    /// Copy the view into a new list.
+    ///
+    /// Time: O(k), for a view of k elements
+    pub fn to_fral(&self) -> Fral<T> {
+        Fral::from_slice(&self.iter().collect::<Vec<_>>())
+    }
+}
+impl<T: ?Sized> Clone for FralSlice<T> {
+    fn clone(&self) -> FralSlice<T> {
+        FralSlice {
+            fral: self.fral.clone(),
+            offset: self.offset,
+            len: self.len,
+        }
+    }
+}
+impl<T: ?Sized + fmt::Debug> fmt::Debug for FralSlice<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// What [`BoundedFral::cons`] does when the list is already at capacity.
+///
+/// [`BoundedFral::cons`]: struct.BoundedFral.html#method.cons
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OnFull {
+    /// Leave the list unchanged and return a [`CapacityError`].
+    ///
+    /// [`CapacityError`]: struct.CapacityError.html
+    Reject,
+    /// Drop the oldest element, i.e. the last one.
+    DropOldest,
+}
+
+/// The error returned when consing onto a full [`BoundedFral`] with [`OnFull::Reject`].
+///
+/// [`BoundedFral`]: struct.BoundedFral.html
+/// [`OnFull::Reject`]: enum.OnFull.html#variant.Reject
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CapacityError {
+    pub capacity: usize,
+}
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "list is at its capacity of {}", self.capacity)
+    }
+}
+impl ::std::error::Error for CapacityError {}
+
+/// The error returned when an index is out of bounds, by methods such as [`try_get`].
+///
+/// [`try_get`]: struct.Fral.html#method.try_get
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct IndexOutOfBounds {
+    pub index: usize,
+    pub len: usize,
+}
+impl fmt::Display for IndexOutOfBounds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "index {} out of bounds for list of length {}",
+            self.index, self.len
+        )
+    }
+}
+impl ::std::error::Error for IndexOutOfBounds {}
+
+/// A functional random access list holding at most a fixed number of elements.
+///
+/// Dropped elements are only released once the underlying list grows to twice the capacity and
+/// is rebuilt, which costs O(capacity). A chain of conses rebuilds at most once every `capacity`
+/// steps, but a version that is one element short of a rebuild pays for it on every `cons`.
+///
+/// # Examples
+///
+/// ```
+/// # use fral::{BoundedFral, OnFull};
+/// let mut recent = BoundedFral::new(3, OnFull::DropOldest);
+/// for x in 0..5 {
+///     recent = recent.cons(x).unwrap();
+/// }
+/// assert_eq!(recent.iter().map(|x| *x).collect::<Vec<_>>(), vec![4, 3, 2]);
+///
+/// let full = BoundedFral::new(1, OnFull::Reject).cons(0).unwrap();
+/// assert!(full.cons(1).is_err());
+/// ```
+pub struct BoundedFral<T: ?Sized> {
+    // only the first `len` elements of `fral` belong to the list.
+    fral: Fral<T>,
+    len: usize,
+    capacity: usize,
+    on_full: OnFull,
+}
+impl<T: ?Sized> BoundedFral<T> {
+    /// Construct an empty list holding at most `capacity` elements.
+    pub fn new(capacity: usize, on_full: OnFull) -> BoundedFral<T> {
+        BoundedFral {
+            fral: Fral::new(),
+            len: 0,
+            capacity,
+            on_full,
+        }
+    }
+    /// Get the maximum number of items in the list.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+    /// Returns true iff the list contains no elements.
+    ///
+    /// Time: O(1)
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Returns true iff the list is at capacity.
+    ///
+    /// Time: O(1)
+    pub fn is_full(&self) -> bool {
+        self.len == self.capacity
+    }
+    /// Get the number of items in the list.
+    ///
+    /// Time: O(1)
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Returns a reference to an element, or `None` if it is out of bounds.
+    ///
+    /// Time: O(log n)
+    pub fn get(&self, index: usize) -> Option<Arc<T>> {
+        if index < self.len {
+            self.fral.get(index)
+        } else {
+            None
+        }
+    }
+    /// Insert an element at the front of the list. If the list is full, this either drops the
+    /// last element or fails, depending on how the list was constructed.
+    ///
+    /// Time: O(capacity) worst case, when the underlying list is rebuilt, and otherwise O(1)
+    pub fn cons<R>(&self, x: R) -> Result<BoundedFral<T>, CapacityError>
+    where
+        R: AsArc<T>,
+    {
+        let len = if !self.is_full() {
+            self.len + 1
+        } else if self.on_full == OnFull::DropOldest {
+            self.len
+        } else {
+            return Err(CapacityError {
+                capacity: self.capacity,
+            });
+        };
+        let mut fral = self.fral.cons(x);
+        // rebuild once the list reaches twice the capacity, without overflowing on huge ones.
+        if fral.len() / 2 >= self.capacity {
+            fral = fral.prefix(len);
+        }
+        Ok(BoundedFral {
+            fral,
+            len,
+            capacity: self.capacity,
+            on_full: self.on_full,
+        })
+    }
+    /// Get the head and tail of the list.
+    ///
+    /// Time: O(1)
+    pub fn uncons(&self) -> Option<(Arc<T>, BoundedFral<T>)> {
+        if self.is_empty() {
+            return None;
+        }
+        self.fral.uncons().map(|(x, fral)| {
+            let rest = BoundedFral {
+                fral,
+                len: self.len - 1,
+                capacity: self.capacity,
+                on_full: self.on_full,
+            };
+            (x, rest)
+        })
+    }
+    /// Iterate over the elements of the list.
+    pub fn iter(&self) -> Take<Iter<T>> {
+        self.fral.iter().take(self.len)
+    }
+    /// Get the elements as an unbounded list.
+    ///
+    /// Time: O(n)
+    pub fn to_fral(&self) -> Fral<T> {
+        self.fral.prefix(self.len)
+    }
+}
+impl<T: ?Sized> Clone for BoundedFral<T> {
+    fn clone(&self) -> BoundedFral<T> {
+        BoundedFral {
+            fral: self.fral.clone(),
+            len: self.len,
+            capacity: self.capacity,
+            on_full: self.on_full,
+        }
+    }
+}
+impl<T: ?Sized + fmt::Debug> fmt::Debug for BoundedFral<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// A batch of edits against a snapshot of a list, applied together by [`commit`].
+///
+/// Indices always refer to positions in the base snapshot, regardless of other queued edits, and
+/// the last edit queued for an index wins. Committing rebuilds only the front of the list up to
+/// the last edited position; everything after it is shared with the base.
+///
+/// [`commit`]: #method.commit
+///
+/// # Examples
+///
+/// ```
+/// # use fral::{Edits, Fral};
+/// let base: Fral<_> = (0..8).rev().collect();
+/// let mut edits = Edits::new(&base);
+/// edits.update(0, 10);
+/// edits.fill(2..4, 20);
+/// edits.remove(5);
+/// let f = edits.commit();
+/// assert_eq!(f.iter().map(|x| *x).collect::<Vec<_>>(), vec![10, 1, 20, 20, 4, 6, 7]);
+/// assert_eq!(base.len(), 8);
+/// ```
+pub struct Edits<T: ?Sized> {
+    base: Fral<T>,
+    // `None` marks a removed element.
+    edits: BTreeMap<usize, Option<Arc<T>>>,
+}
+impl<T: ?Sized> Edits<T> {
+    /// Start a batch of edits against a snapshot of the list.
+    pub fn new(base: &Fral<T>) -> Edits<T> {
+        Edits {
+            base: base.clone(),
+            edits: BTreeMap::new(),
+        }
+    }
+    /// Get the snapshot the edits apply to.
+    pub fn base(&self) -> &Fral<T> {
+        &self.base
+    }
+    /// Returns true iff no edits are queued.
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+    /// Replace the element at `index`.
+    ///
+    /// Time: O(log e), for e queued edits
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn update<R: AsArc<T>>(&mut self, index: usize, x: R) {
+        self.check_index(index);
+        self.edits.insert(index, Some(x.as_arc()));
+    }
+    /// Replace every element in the range with `x`, sharing the one allocation.
+    ///
+    /// Time: O(k log e), for a range of k elements and e queued edits
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is decreasing or extends past the end of the list.
+    pub fn fill<Rg: RangeBounds<usize>, R: AsArc<T>>(&mut self, range: Rg, x: R) {
+        let (start, end) = resolve_range(&range, self.base.len());
+        let x = x.as_arc();
+        for index in start..end {
+            self.edits.insert(index, Some(x.clone()));
+        }
+    }
+    /// Remove the element at `index`.
+    ///
+    /// Time: O(log e), for e queued edits
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) {
+        self.check_index(index);
+        self.edits.insert(index, None);
+    }
+    /// Apply all of the queued edits in one pass.
+    ///
+    /// Time: O(m + log n), where m is the last edited index
+    pub fn commit(self) -> Fral<T> {
+        let end = match self.edits.keys().next_back() {
+            Some(&last) => last + 1,
+            None => return self.base,
+        };
+        let mut front = Vec::with_capacity(end);
+        let mut edits = self.edits.into_iter().peekable();
+        self.base.pair().all(&mut |x| {
+            let index = front.len();
+            match edits.peek() {
+                Some(&(i, _)) if i == index => front.push(edits.next().unwrap().1),
+                _ => front.push(Some(x.clone())),
+            }
+            front.len() < end
+        });
+        front
+            .into_iter()
+            .rev()
+            .flatten()
+            .fold(self.base.suffix(end), |f, x| f.cons(x))
+    }
+    fn check_index(&self, index: usize) {
+        assert!(
+            index < self.base.len(),
+            "index {} out of bounds for list of length {}",
+            index,
+            self.base.len()
+        );
+    }
+}
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Edits<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Edits")
+            .field("base", &self.base)
+            .field("edits", &self.edits)
+            .finish()
+    }
+}
+
+/// A hash-consing table which makes equal elements, and equal trees, share the same allocation.
+///
+/// Elements are compared by value. Trees are compared by the identity of their element and
+/// subtrees, so trees built from interned parts are shared whenever they are equal.
+///
+/// The interner holds onto everything it has seen until it is cleared or dropped.
+pub struct Interner<T: ?Sized> {
+    elements: HashSet<Arc<T>>,
+    // keyed by the addresses of the element and subtrees, which the value keeps alive.
+    trees: HashMap<(usize, usize, usize), Arc<Tree<T>>>,
+    hits: usize,
+    misses: usize,
+}
+
+/// Counts describing the contents and effectiveness of an [`Interner`].
+///
+/// [`Interner`]: struct.Interner.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct InternerStats {
+    /// The number of distinct elements held.
+    pub elements: usize,
+    /// The number of distinct trees held.
+    pub trees: usize,
+    /// The number of elements and trees which were replaced by an existing one.
+    pub hits: usize,
+    /// The number of elements and trees which were new.
+    pub misses: usize,
+}
+
+impl<T: ?Sized + Eq + Hash> Interner<T> {
+    /// Construct an empty interner.
+    pub fn new() -> Interner<T> {
+        Interner {
+            elements: HashSet::new(),
+            trees: HashMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+    /// Get the shared element equal to `x`, adding it if there is none.
+    pub fn intern<R: AsArc<T>>(&mut self, x: R) -> Arc<T> {
+        let x = x.as_arc();
+        if let Some(y) = self.elements.get(&*x) {
+            self.hits += 1;
+            return y.clone();
+        }
+        self.misses += 1;
+        self.elements.insert(x.clone());
+        x
+    }
+    fn tree(&mut self, tree: Arc<Tree<T>>) -> Arc<Tree<T>> {
+        let key = match *tree {
+            Leaf(ref x) => (address(x), 0, 0),
+            Node(ref x, ref t1, ref t2) => (address(x), address(t1), address(t2)),
+        };
+        match self.trees.get(&key) {
+            Some(shared) => {
+                self.hits += 1;
+                return shared.clone();
+            }
+            None => self.misses += 1,
+        }
+        self.trees.insert(key, tree.clone());
+        tree
+    }
+    /// Get counts describing the interner.
+    pub fn stats(&self) -> InternerStats {
+        InternerStats {
+            elements: self.elements.len(),
+            trees: self.trees.len(),
+            hits: self.hits,
+            misses: self.misses,
+        }
+    }
+    /// Release everything held by the interner and reset its counts. Lists built with it are
+    /// unaffected.
+    pub fn clear(&mut self) {
+        self.elements.clear();
+        self.trees.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+}
+impl<T: ?Sized + Eq + Hash> Default for Interner<T> {
+    fn default() -> Interner<T> {
+        Interner::new()
+    }
+}
+
+/// Counts of the parts of a list which are shared with other lists, created with
+/// [`sharing_report`].
+///
+/// [`sharing_report`]: struct.Fral.html#method.sharing_report
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SharingReport {
+    /// The number of spine cells and tree nodes.
+    pub nodes: usize,
+    /// The number of nodes which are also part of another list.
+    pub shared_nodes: usize,
+    /// The number of elements which are also held by another list, or from outside of any list.
+    pub shared_elements: usize,
+}
+impl SharingReport {
+    /// Get the number of nodes which belong to this list alone, and which dropping it would
+    /// release.
+    pub fn unique_nodes(&self) -> usize {
+        self.nodes - self.shared_nodes
+    }
+}
+
+/// The identity of an element's allocation, created with [`iter_ids`].
+///
+/// Snapshots which share an element give it the same identity, so this can key caches of
+/// results computed from elements. An identity is only unique while the element is alive: once
+/// every list holding it is dropped, its allocation, and so its identity, may be reused.
+///
+/// [`iter_ids`]: struct.Fral.html#method.iter_ids
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ElementId(usize);
+
+fn address<T: ?Sized>(x: &Arc<T>) -> usize {
+    &**x as *const T as *const () as usize
+}
+
+use self::Pair::*;
+#[derive(Clone, Hash, Debug, PartialOrd, Ord, PartialEq, Eq)]
+enum Pair<T: ?Sized> {
+    Nil,
+    Cons((usize, Arc<Tree<T>>), Arc<Pair<T>>),
+}
+impl<T: ?Sized> Pair<T> {
+    /// The sizes of the trees in the canonical skew-binary decomposition of `n`, which is found
+    /// greedily from the largest tree. The sizes are descending.
+    fn decompose(mut n: usize) -> Vec<usize> {
+        let mut sizes = Vec::new();
+        while n > 0 {
+            let mut size = 1;
+            while 2 * size < n {
+                size = 2 * size + 1;
+            }
+            sizes.push(size);
+            n -= size;
+        }
+        sizes
+    }
+    /// Build the spine holding `n` copies of `x`, sharing every tree and subtree of each size.
+    fn repeat(x: &Arc<T>, n: usize) -> Pair<T> {
+        // perfect trees of increasing size, each made of two copies of the last.
+        let mut trees = vec![(1, Arc::new(Leaf(x.clone())))];
+        let mut pair = Nil;
+        for size in Pair::<T>::decompose(n) {
+            while trees[trees.len() - 1].0 < size {
+                let (half, ref tree) = trees[trees.len() - 1];
+                let tree = Arc::new(Node(x.clone(), tree.clone(), tree.clone()));
+                trees.push((2 * half + 1, tree));
+            }
+            let tree = trees.iter().find(|&&(tsize, _)| tsize == size).unwrap();
+            pair = Cons((size, tree.1.clone()), Arc::new(pair));
+        }
+        pair
+    }
+    /// Build the spine holding the items in order, with the canonical decomposition.
+    fn from_slice(items: &[Arc<T>]) -> Pair<T> {
+        // sizes are descending, so the last tree comes first.
+        let mut end = items.len();
+        let mut pair = Nil;
+        for size in Pair::<T>::decompose(items.len()) {
+            let tree = Tree::from_slice(&items[end - size..end]);
+            pair = Cons((size, Arc::new(tree)), Arc::new(pair));
+            end -= size;
+        }
+        pair
+    }
+    /// Visit the elements in order until `f` returns false. Returns false iff stopped early.
+    fn all<'a, F: FnMut(&'a Arc<T>) -> bool>(&'a self, f: &mut F) -> bool {
+        let mut pair = self;
+        while let Cons((_, ref tree), ref rest) = *pair {
+            if !tree.all(f) {
+                return false;
+            }
+            pair = rest;
+        }
+        true
+    }
+    /// Compare with a spine of the same shape, where shared subtrees are equal without looking
+    /// at their elements.
+    fn eq_shared(&self, other: &Pair<T>) -> bool
+    where
+        T: PartialEq,
+    {
+        match (self, other) {
+            (Cons((_, t1), r1), Cons((_, t2), r2)) => {
+                (Arc::ptr_eq(t1, t2) || t1.eq_shared(t2))
+                    && (Arc::ptr_eq(r1, r2) || r1.eq_shared(r2))
+            }
+            _ => true,
+        }
+    }
+    /// Visit the elements in reverse order until `f` returns false. Returns false iff stopped
+    /// early.
+    fn rall<'a, F: FnMut(&'a Arc<T>) -> bool>(&'a self, f: &mut F) -> bool {
+        match *self {
+            Nil => true,
+            Cons((_, ref tree), ref rest) => rest.rall(f) && tree.rall(f),
+        }
+    }
+    fn get(&self, index: usize) -> Option<&Arc<T>> {
+        match *self {
+            Nil => None,
+            Cons((size, ref tree), ref cdr) => {
+                if index < size {
+                    tree.lookup(size, index)
+                } else {
+                    cdr.get(index - size)
+                }
+            }
+        }
+    }
+    /// Get the element at `index`, which must be in bounds, copying whatever is shared on the
+    /// path to it.
+    fn get_mut(pair: &mut Arc<Pair<T>>, index: usize) -> &mut T
+    where
+        T: Clone,
+    {
+        match *Arc::make_mut(pair) {
+            Nil => unreachable!(),
+            Cons((size, ref mut tree), ref mut rest) => {
+                if index < size {
+                    Tree::get_mut(tree, size, index)
+                } else {
+                    Pair::get_mut(rest, index - size)
+                }
+            }
+        }
+    }
+    /// Get the element at `index`, which must be in bounds, if nothing on the path to it is
+    /// shared.
+    fn get_mut_unique(pair: &mut Arc<Pair<T>>, index: usize) -> Option<&mut T> {
+        match *Arc::get_mut(pair)? {
+            Nil => unreachable!(),
+            Cons((size, ref mut tree), ref mut rest) => {
+                if index < size {
+                    Tree::get_mut_unique(tree, size, index)
+                } else {
+                    Pair::get_mut_unique(rest, index - size)
+                }
+            }
+        }
+    }
+    /// Get the element at `index`, which must be in bounds.
+    unsafe fn get_unchecked(&self, index: usize) -> &T {
+        let mut pair = self;
+        let mut index = index;
+        loop {
+            match *pair {
+                Cons((size, ref tree), ref rest) => {
+                    if index < size {
+                        return tree.lookup_unchecked(size, index);
+                    }
+                    index -= size;
+                    pair = rest;
+                }
+                Nil => {
+                    debug_assert!(false, "index out of bounds");
+                    unsafe { ::std::hint::unreachable_unchecked() }
+                }
+            }
+        }
+    }
+    /// Replace the element at `index`, which must be in bounds, with `f` applied to it.
+    fn update<F: FnOnce(&Arc<T>) -> Arc<T>>(&self, index: usize, f: F) -> Pair<T> {
+        match *self {
+            Nil => unreachable!(),
+            Cons((size, ref tree), ref rest) => {
+                if index < size {
+                    Cons((size, Arc::new(tree.update(size, index, f))), rest.clone())
+                } else {
+                    Cons((size, tree.clone()), Arc::new(rest.update(index - size, f)))
+                }
+            }
+        }
+    }
+    /// Build a spine of the same shape with `f` applied to each element, in order.
+    fn map<U: ?Sized, F: FnMut(&T) -> Arc<U>>(&self, f: &mut F) -> Pair<U> {
+        match *self {
+            Nil => Nil,
+            Cons((size, ref tree), ref rest) => {
+                let tree = Arc::new(tree.map(f));
+                Cons((size, tree), Arc::new(rest.map(f)))
+            }
+        }
+    }
+    /// Look up the sorted, in-bounds queries of index and position, storing each element found
+    /// at its position in `found`.
+    fn lookup_many(&self, queries: &[(usize, usize)], found: &mut [Option<Arc<T>>]) {
+        let mut pair = self;
+        let mut offset = 0;
+        let mut queries = queries;
+        while let Cons((size, ref tree), ref rest) = *pair {
+            if queries.is_empty() {
+                break;
+            }
+            let split = queries.partition_point(|&(index, _)| index < offset + size);
+            if split > 0 {
+                tree.lookup_many(size, offset, &queries[..split], found);
+            }
+            queries = &queries[split..];
+            offset += size;
+            pair = rest;
+        }
+    }
+    /// Apply the updates, whose indices are sorted, distinct, in bounds, and offset by
+    /// `offset` from the front of this spine.
+    fn update_many(&self, offset: usize, updates: &[(usize, Arc<T>)]) -> Pair<T> {
+        match *self {
+            Nil => unreachable!(),
+            Cons((size, ref tree), ref rest) => {
+                let split = updates.partition_point(|&(index, _)| index < offset + size);
+                let tree = if split == 0 {
+                    tree.clone()
+                } else {
+                    Arc::new(tree.update_many(size, offset, &updates[..split]))
+                };
+                let rest = if split == updates.len() {
+                    rest.clone()
+                } else {
+                    Arc::new(rest.update_many(offset + size, &updates[split..]))
+                };
+                Cons((size, tree), rest)
+            }
+        }
+    }
+    fn cons(&self, x: Arc<T>) -> Self {
+        match *self {
+            Nil => Cons((1, Arc::new(Leaf(x))), Arc::new(Nil)),
+            Cons((size1, ref t1), ref nxt) => match **nxt {
+                Cons((size2, ref t2), ref rest) => {
+                    if size1 == size2 {
+                        Cons(
+                            (1 + size1 + size2, Arc::new(Node(x, t1.clone(), t2.clone()))),
+                            rest.clone(),
+                        )
+                    } else {
+                        Cons(
+                            (1, Arc::new(Leaf(x))),
+                            Arc::new(Cons(
+                                (size1, t1.clone()),
+                                Arc::new(Cons((size2, t2.clone()), rest.clone())),
+                            )),
+                        )
+                    }
+                }
+                Nil => Cons(
+                    (1, Arc::new(Leaf(x))),
+                    Arc::new(Cons((size1, t1.clone()), Arc::new(Nil))),
+                ),
+            },
+        }
+    }
+    /// Cons onto a shared spine, which becomes the tail as is unless its first two trees merge.
+    fn cons_shared(pair: &Arc<Pair<T>>, x: Arc<T>) -> Pair<T> {
+        if let Cons((size1, ref t1), ref nxt) = **pair {
+            if let Cons((size2, ref t2), ref rest) = **nxt {
+                if size1 == size2 {
+                    let tree = Node(x, t1.clone(), t2.clone());
+                    return Cons((1 + size1 + size2, Arc::new(tree)), rest.clone());
+                }
+            }
+        }
+        Cons((1, Arc::new(Leaf(x))), pair.clone())
+    }
+    fn uncons(&self) -> Option<(Arc<T>, Arc<Self>)> {
+        match *self {
+            Nil => None,
+            Cons((_, ref t), _) => match **t {
+                Leaf(ref x) | Node(ref x, _, _) => Some((x.clone(), self.tail()?)),
+            },
+        }
+    }
+    fn tail(&self) -> Option<Arc<Self>> {
+        match *self {
+            Nil => None,
+            Cons((size, ref t), ref rest) => match **t {
+                Leaf(_) => Some(rest.clone()),
+                Node(_, ref t1, ref t2) => {
+                    let half = size / 2;
+                    Some(Arc::new(Cons(
+                        (half, t1.clone()),
+                        Arc::new(Cons((half, t2.clone()), rest.clone())),
+                    )))
+                }
+            },
+        }
+    }
+}
+
+use self::Tree::*;
+#[derive(Clone, Hash, Debug, PartialOrd, Ord, PartialEq, Eq)]
+enum Tree<T: ?Sized> {
+    Leaf(Arc<T>),
+    Node(Arc<T>, Arc<Tree<T>>, Arc<Tree<T>>),
+}
+impl<T: ?Sized> Tree<T> {
+    /// Build the perfect tree holding the items, whose length must be 2^k - 1, in order.
+    fn from_slice(items: &[Arc<T>]) -> Tree<T> {
+        if items.len() == 1 {
+            Leaf(items[0].clone())
+        } else {
+            let half = items.len() / 2;
+            Node(
+                items[0].clone(),
+                Arc::new(Tree::from_slice(&items[1..=half])),
+                Arc::new(Tree::from_slice(&items[half + 1..])),
+            )
+        }
+    }
+    /// Drop the first `n < size` elements of the tree, putting the remaining trees in front of
+    /// `rest`.
+    fn drop_front(tree: &Arc<Tree<T>>, size: usize, n: usize, rest: Arc<Pair<T>>) -> Arc<Pair<T>> {
+        if n == 0 {
+            return Arc::new(Cons((size, tree.clone()), rest));
+        }
+        match **tree {
+            Leaf(_) => rest,
+            Node(_, ref t1, ref t2) => {
+                let half = size / 2;
+                if n <= half {
+                    let rest = Arc::new(Cons((half, t2.clone()), rest));
+                    Tree::drop_front(t1, half, n - 1, rest)
+                } else {
+                    Tree::drop_front(t2, half, n - 1 - half, rest)
+                }
+            }
+        }
+    }
+    fn all<'a, F: FnMut(&'a Arc<T>) -> bool>(&'a self, f: &mut F) -> bool {
+        match *self {
+            Leaf(ref x) => f(x),
+            Node(ref x, ref t1, ref t2) => f(x) && t1.all(f) && t2.all(f),
+        }
+    }
+    /// Compare with a tree of the same size, where shared subtrees are equal without looking at
+    /// their elements.
+    fn eq_shared(&self, other: &Tree<T>) -> bool
+    where
+        T: PartialEq,
+    {
+        match (self, other) {
+            (Leaf(x), Leaf(y)) => **x == **y,
+            (Node(x, a1, a2), Node(y, b1, b2)) => {
+                **x == **y
+                    && (Arc::ptr_eq(a1, b1) || a1.eq_shared(b1))
+                    && (Arc::ptr_eq(a2, b2) || a2.eq_shared(b2))
+            }
+            _ => false,
+        }
+    }
+    fn rall<'a, F: FnMut(&'a Arc<T>) -> bool>(&'a self, f: &mut F) -> bool {
+        match *self {
+            Leaf(ref x) => f(x),
+            Node(ref x, ref t1, ref t2) => t2.rall(f) && t1.rall(f) && f(x),
+        }
+    }
+    fn is_unique(tree: &Arc<Tree<T>>) -> bool {
+        Arc::strong_count(tree) == 1
+            && match **tree {
+                Leaf(ref x) => Arc::strong_count(x) == 1,
+                Node(ref x, ref t1, ref t2) => {
+                    Arc::strong_count(x) == 1 && Tree::is_unique(t1) && Tree::is_unique(t2)
+                }
+            }
+    }
+    /// Count the nodes and elements of the tree into `report`, where `shared` is whether a node
+    /// above it is shared.
+    fn report(tree: &Arc<Tree<T>>, shared: bool, report: &mut SharingReport) {
+        let shared = shared || Arc::strong_count(tree) > 1;
+        report.nodes += 1;
+        if shared {
+            report.shared_nodes += 1;
+        }
+        match **tree {
+            Leaf(ref x) => {
+                if shared || Arc::strong_count(x) > 1 {
+                    report.shared_elements += 1;
+                }
+            }
+            Node(ref x, ref t1, ref t2) => {
+                if shared || Arc::strong_count(x) > 1 {
+                    report.shared_elements += 1;
+                }
+                Tree::report(t1, shared, report);
+                Tree::report(t2, shared, report);
+            }
+        }
+    }
+    #[cfg(any(test, kani))]
+    fn is_well_formed(&self, size: usize) -> bool {
+        match *self {
+            Leaf(_) => size == 1,
+            Node(_, ref t1, ref t2) => {
+                let half = size / 2;
+                size > 1 && t1.is_well_formed(half) && t2.is_well_formed(half)
+            }
+        }
+    }
+    /// Replace the element at `index < size` with `f` applied to it.
+    fn update<F: FnOnce(&Arc<T>) -> Arc<T>>(&self, size: usize, index: usize, f: F) -> Tree<T> {
+        match *self {
+            Leaf(ref x) => Leaf(f(x)),
+            Node(ref x, ref t1, ref t2) => {
+                let half = size / 2;
+                if index == 0 {
+                    Node(f(x), t1.clone(), t2.clone())
+                } else if index <= half {
+                    let t1 = Arc::new(t1.update(half, index - 1, f));
+                    Node(x.clone(), t1, t2.clone())
+                } else {
+                    let t2 = Arc::new(t2.update(half, index - 1 - half, f));
+                    Node(x.clone(), t1.clone(), t2)
+                }
+            }
+        }
+    }
+    /// Build a tree of the same shape with `f` applied to each element, in order.
+    fn map<U: ?Sized, F: FnMut(&T) -> Arc<U>>(&self, f: &mut F) -> Tree<U> {
+        match *self {
+            Leaf(ref x) => Leaf(f(x)),
+            Node(ref x, ref t1, ref t2) => {
+                let x = f(x);
+                let t1 = Arc::new(t1.map(f));
+                Node(x, t1, Arc::new(t2.map(f)))
+            }
+        }
+    }
+    /// Apply the nonempty updates, whose indices are sorted, distinct, and within the tree once
+    /// `offset` is subtracted.
+    fn update_many(&self, size: usize, offset: usize, updates: &[(usize, Arc<T>)]) -> Tree<T> {
+        let (x, updates) = match updates[0] {
+            (index, ref x) if index == offset => (Some(x.clone()), &updates[1..]),
+            _ => (None, updates),
+        };
+        match *self {
+            Leaf(ref y) => Leaf(x.unwrap_or_else(|| y.clone())),
+            Node(ref y, ref t1, ref t2) => {
+                let half = size / 2;
+                let split = updates.partition_point(|&(index, _)| index <= offset + half);
+                let t1 = if split == 0 {
+                    t1.clone()
+                } else {
+                    Arc::new(t1.update_many(half, offset + 1, &updates[..split]))
+                };
+                let t2 = if split == updates.len() {
+                    t2.clone()
+                } else {
+                    Arc::new(t2.update_many(half, offset + 1 + half, &updates[split..]))
+                };
+                Node(x.unwrap_or_else(|| y.clone()), t1, t2)
+            }
+        }
+    }
+    /// Look up the sorted queries of index and position, which must all lie within this tree of
+    /// `size` elements starting at `offset`.
+    fn lookup_many(
+        &self,
+        size: usize,
+        offset: usize,
+        queries: &[(usize, usize)],
+        found: &mut [Option<Arc<T>>],
+    ) {
+        let (x, t1, t2) = match *self {
+            Leaf(ref x) => (x, None, None),
+            Node(ref x, ref t1, ref t2) => (x, Some(t1), Some(t2)),
+        };
+        let here = queries.partition_point(|&(index, _)| index == offset);
+        for &(_, position) in &queries[..here] {
+            found[position] = Some(x.clone());
+        }
+        let queries = &queries[here..];
+        if let (Some(t1), Some(t2)) = (t1, t2) {
+            let half = size / 2;
+            let split = queries.partition_point(|&(index, _)| index <= offset + half);
+            if split > 0 {
+                t1.lookup_many(half, offset + 1, &queries[..split], found);
+            }
+            if split < queries.len() {
+                t2.lookup_many(half, offset + 1 + half, &queries[split..], found);
+            }
+        }
+    }
+    /// Get the element at `index < size`, copying whatever is shared on the path to it.
+    fn get_mut(tree: &mut Arc<Tree<T>>, size: usize, index: usize) -> &mut T
+    where
+        T: Clone,
+    {
+        match *Arc::make_mut(tree) {
+            Leaf(ref mut x) => Arc::make_mut(x),
+            Node(ref mut x, ref mut t1, ref mut t2) => {
+                let half = size / 2;
+                if index == 0 {
+                    Arc::make_mut(x)
+                } else if index <= half {
+                    Tree::get_mut(t1, half, index - 1)
+                } else {
+                    Tree::get_mut(t2, half, index - 1 - half)
+                }
+            }
+        }
+    }
+    /// Get the element at `index < size`, if nothing on the path to it is shared.
+    fn get_mut_unique(tree: &mut Arc<Tree<T>>, size: usize, index: usize) -> Option<&mut T> {
+        match *Arc::get_mut(tree)? {
+            Leaf(ref mut x) => Arc::get_mut(x),
+            Node(ref mut x, ref mut t1, ref mut t2) => {
+                let half = size / 2;
+                if index == 0 {
+                    Arc::get_mut(x)
+                } else if index <= half {
+                    Tree::get_mut_unique(t1, half, index - 1)
+                } else {
+                    Tree::get_mut_unique(t2, half, index - 1 - half)
+                }
+            }
+        }
+    }
+    /// Get the element at `index < size`, descending without checking the bounds of leaves.
+    fn lookup_unchecked(&self, size: usize, index: usize) -> &T {
+        let (mut tree, mut size, mut index) = (self, size, index);
+        loop {
+            match *tree {
+                Leaf(ref x) => {
+                    debug_assert_eq!(index, 0);
+                    return x;
+                }
+                Node(ref x, ref t1, ref t2) => {
+                    if index == 0 {
+                        return x;
+                    }
+                    let half = size / 2;
+                    if index <= half {
+                        tree = t1;
+                        index -= 1;
+                    } else {
+                        tree = t2;
+                        index -= 1 + half;
+                    }
+                    size = half;
+                }
+            }
+        }
+    }
+    fn lookup(&self, size: usize, index: usize) -> Option<&Arc<T>> {
+        match (index, self) {
+            (0, Leaf(x)) | (0, Node(x, _, _)) => Some(x),
+            (_, Leaf(_)) => None,
+            (i, Node(_, t1, t2)) => {
+                let half = size / 2;
+                if i <= half {
+                    t1.lookup(half, i - 1)
+                } else {
+                    t2.lookup(half, i - 1 - half)
+                }
+            }
+        }
+    }
+}
+
+pub struct Iter<T: ?Sized> {
+    fral: Fral<T>,
+    // the number of elements left, which is less than the length of fral once elements have
+    // been taken from the back.
+    len: usize,
+    // the parts of fral still to visit from the back, the next one last. This is only filled
+    // once the first element is taken from the back.
+    back: Vec<Back<T>>,
+}
+/// A part of a list still to be visited in reverse.
+enum Back<T: ?Sized> {
+    Tree(Arc<Tree<T>>),
+    Item(Arc<T>),
+}
+impl<T: ?Sized> Iter<T> {
+    fn new(fral: Fral<T>) -> Iter<T> {
+        Iter {
+            len: fral.size,
+            fral,
+            back: Vec::new(),
+        }
+    }
+}
+impl<T: ?Sized> Iterator for Iter<T> {
+    type Item = Arc<T>;
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let (item, fral) = self.fral.uncons()?;
+        self.fral = fral;
+        self.len -= 1;
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
+
+    /// Skip whole trees rather than visiting the elements in between, so this and `skip` take
+    /// O(log n).
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if n >= self.len {
+            self.len = 0;
+            self.fral = Fral::new();
+            self.back.clear();
+            return None;
+        }
+        self.fral = self.fral.suffix(n);
+        self.len -= n;
+        self.next()
+    }
+}
+/// Iteration from the back walks the trees in reverse, so taking every element this way is O(n)
+/// after O(log n) to start.
+///
+/// # Examples
+///
+/// ```
+/// # use fral::Fral;
+/// let f: Fral<_> = (0..5).rev().collect();
+/// assert_eq!(f.iter().rev().map(|x| *x).collect::<Vec<_>>(), vec![4, 3, 2, 1, 0]);
+/// let mut iter = f.iter();
+/// assert_eq!(iter.next_back().map(|x| *x), Some(4));
+/// assert_eq!(iter.next().map(|x| *x), Some(0));
+/// assert_eq!(iter.len(), 3);
+/// ```
+impl<T: ?Sized> DoubleEndedIterator for Iter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        if self.back.is_empty() {
+            // nothing has been taken from the back yet, since the back would otherwise hold
+            // everything not taken from the front.
+            let mut pair = self.fral.pair();
+            while let Cons((_, ref tree), ref rest) = *pair {
+                self.back.push(Back::Tree(tree.clone()));
+                pair = rest;
+            }
+        }
+        self.len -= 1;
+        loop {
+            // preorder is reversed by visiting the right subtree, then the left, then the root.
+            match self.back.pop()? {
+                Back::Item(x) => return Some(x),
+                Back::Tree(tree) => match *tree {
+                    Leaf(ref x) => return Some(x.clone()),
+                    Node(ref x, ref t1, ref t2) => {
+                        self.back.push(Back::Item(x.clone()));
+                        self.back.push(Back::Tree(t1.clone()));
+                        self.back.push(Back::Tree(t2.clone()));
+                    }
+                },
+            }
+        }
+    }
+}
+impl<T: ?Sized> ExactSizeIterator for Iter<T> {}
+
+/// An upper bound on the depth of any tree, since a tree's size fits in a `usize`.
+const MAX_DEPTH: usize = ::std::mem::size_of::<usize>() * 8;
+
+/// An iterator over references to the elements of a [`Fral`], created with [`iter_ref`].
+///
+/// [`Fral`]: struct.Fral.html
+/// [`iter_ref`]: struct.Fral.html#method.iter_ref
+pub struct IterRef<'a, T: ?Sized> {
+    // the trees after the current one.
+    pair: &'a Pair<T>,
+    // the subtrees of the current tree still to visit, the next one last.
+    stack: [Option<&'a Tree<T>>; MAX_DEPTH],
+    depth: usize,
+    len: usize,
+}
+impl<'a, T: ?Sized> Iterator for IterRef<'a, T> {
+    type Item = &'a T;
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        let tree = if self.depth > 0 {
+            self.depth -= 1;
+            self.stack[self.depth].unwrap()
+        } else {
+            match *self.pair {
+                Nil => return None,
+                Cons((_, ref tree), ref rest) => {
+                    self.pair = rest;
+                    tree
+                }
+            }
+        };
+        self.len -= 1;
+        match *tree {
+            Leaf(ref x) => Some(x),
+            Node(ref x, ref t1, ref t2) => {
+                self.stack[self.depth] = Some(t2);
+                self.stack[self.depth + 1] = Some(t1);
+                self.depth += 2;
+                Some(x)
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+impl<'a, T: ?Sized> ExactSizeIterator for IterRef<'a, T> {}
+
+/// The future returned by [`Fral::from_stream`].
+///
+/// [`Fral::from_stream`]: struct.Fral.html#method.from_stream
+#[cfg(feature = "async")]
+pub struct FromStream<S, T: ?Sized> {
+    stream: S,
+    fral: Fral<T>,
+}
+#[cfg(feature = "async")]
+impl<S, T: ?Sized> Future for FromStream<S, T>
+where
+    S: Stream + Unpin,
+    S::Item: AsArc<T>,
+{
+    type Output = Fral<T>;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Fral<T>> {
+        let this = &mut *self;
+        loop {
+            match Pin::new(&mut this.stream).poll_next(cx) {
+                Poll::Ready(Some(x)) => this.fral = this.fral.cons(x),
+                Poll::Ready(None) => return Poll::Ready(mem::take(&mut this.fral)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// The future returned by [`Fral::from_stream_ordered`].
+///
+/// [`Fral::from_stream_ordered`]: struct.Fral.html#method.from_stream_ordered
+#[cfg(feature = "async")]
+pub struct FromStreamOrdered<S, T: ?Sized> {
+    stream: S,
+    items: Vec<Arc<T>>,
+}
+#[cfg(feature = "async")]
+impl<S, T: ?Sized> Future for FromStreamOrdered<S, T>
+where
+    S: Stream + Unpin,
+    S::Item: AsArc<T>,
+{
+    type Output = Fral<T>;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Fral<T>> {
+        let this = &mut *self;
+        loop {
+            match Pin::new(&mut this.stream).poll_next(cx) {
+                Poll::Ready(Some(x)) => this.items.push(x.as_arc()),
+                Poll::Ready(None) => {
+                    let items = mem::take(&mut this.items);
+                    return Poll::Ready(Fral::from_slice(&items));
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// An iterator over the trees of a list, created with [`trees`].
+///
+/// [`trees`]: struct.Fral.html#method.trees
+pub struct Trees<'a, T: ?Sized> {
+    pair: &'a Pair<T>,
+}
+impl<'a, T: ?Sized> Iterator for Trees<'a, T> {
+    type Item = TreeRef<'a, T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        match *self.pair {
+            Nil => None,
+            Cons((size, ref tree), ref rest) => {
+                self.pair = rest;
+                Some(TreeRef { size, tree })
+            }
+        }
+    }
+}
+
+/// A read-only view of a complete binary tree within a list.
+pub struct TreeRef<'a, T: ?Sized> {
+    size: usize,
+    tree: &'a Tree<T>,
+}
+impl<'a, T: ?Sized> TreeRef<'a, T> {
+    /// Get the number of elements in the tree.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+    /// Get the element at the root of the tree.
+    pub fn value(&self) -> &'a Arc<T> {
+        match *self.tree {
+            Leaf(ref x) | Node(ref x, _, _) => x,
+        }
+    }
+    /// Get the left subtree, or `None` if the tree is a leaf.
+    pub fn left(&self) -> Option<TreeRef<'a, T>> {
+        match *self.tree {
+            Leaf(_) => None,
+            Node(_, ref t1, _) => Some(TreeRef {
+                size: self.size / 2,
+                tree: t1,
+            }),
+        }
+    }
+    /// Get the right subtree, or `None` if the tree is a leaf.
+    pub fn right(&self) -> Option<TreeRef<'a, T>> {
+        match *self.tree {
+            Leaf(_) => None,
+            Node(_, _, ref t2) => Some(TreeRef {
+                size: self.size / 2,
+                tree: t2,
+            }),
+        }
+    }
+}
+impl<'a, T: ?Sized> Clone for TreeRef<'a, T> {
+    fn clone(&self) -> TreeRef<'a, T> {
+        *self
+    }
+}
+impl<'a, T: ?Sized> Copy for TreeRef<'a, T> {}
+impl<'a, T: ?Sized + fmt::Debug> fmt::Debug for TreeRef<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TreeRef")
+            .field("size", &self.size)
+            .field("value", self.value())
+            .finish()
+    }
+}
+
+/// A handle to one element of a [`Fral`], created with [`at`].
+///
+/// [`Fral`]: struct.Fral.html
+/// [`at`]: struct.Fral.html#method.at
+pub struct At<'a, T: ?Sized> {
+    fral: &'a Fral<T>,
+    index: usize,
+    // the spine cells up to and including the one holding the element's tree.
+    spine: Vec<&'a Pair<T>>,
+    // the trees above the element's, each with whether the path goes to the left subtree.
+    trees: Vec<(&'a Tree<T>, bool)>,
+    // the tree whose root is the element.
+    target: &'a Tree<T>,
+}
+impl<'a, T: ?Sized> At<'a, T> {
+    /// Get the index of the element.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+    /// Get the element.
+    ///
+    /// Time: O(1)
+    pub fn get(&self) -> Arc<T> {
+        self.value().clone()
+    }
+    /// Replace the element, returning the new list. Only the path to the element is copied.
+    ///
+    /// Time: O(log n)
+    pub fn set<R: AsArc<T>>(&self, x: R) -> Fral<T> {
+        self.rebuild(x.as_arc())
+    }
+    /// Replace the element with `f` applied to it, returning the new list.
+    ///
+    /// Time: O(log n)
+    pub fn modify<F>(&self, f: F) -> Fral<T>
+    where
+        T: Sized,
+        F: FnOnce(&T) -> T,
+    {
+        self.rebuild(Arc::new(f(self.value())))
+    }
+    fn value(&self) -> &'a Arc<T> {
+        match *self.target {
+            Leaf(ref x) | Node(ref x, _, _) => x,
+        }
+    }
+    /// Copy the path to the element, from the bottom up, with `x` in its place.
+    fn rebuild(&self, x: Arc<T>) -> Fral<T> {
+        let mut tree = match *self.target {
+            Leaf(_) => Leaf(x),
+            Node(_, ref t1, ref t2) => Node(x, t1.clone(), t2.clone()),
+        };
+        for &(node, left) in self.trees.iter().rev() {
+            tree = match *node {
+                Leaf(_) => unreachable!(),
+                Node(ref y, ref t1, ref t2) => {
+                    if left {
+                        Node(y.clone(), Arc::new(tree), t2.clone())
+                    } else {
+                        Node(y.clone(), t1.clone(), Arc::new(tree))
+                    }
+                }
+            };
+        }
+        let mut cells = self.spine.iter().rev();
+        let mut pair = match **cells.next().unwrap() {
+            Cons((size, _), ref rest) => Cons((size, Arc::new(tree)), rest.clone()),
+            Nil => unreachable!(),
+        };
+        for cell in cells {
+            pair = match **cell {
+                Cons((size, ref tree), _) => Cons((size, tree.clone()), Arc::new(pair)),
+                Nil => unreachable!(),
+            };
+        }
+        Fral::from_pair(self.fral.size, Arc::new(pair))
+    }
+}
+impl<'a, T: ?Sized + fmt::Debug> fmt::Debug for At<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("At")
+            .field("index", &self.index)
+            .field("value", self.value())
+            .finish()
+    }
+}
+
+/// An iterator over groups of elements, created with [`chunks`].
+///
+/// [`chunks`]: struct.Fral.html#method.chunks
+pub struct Chunks<T: ?Sized> {
+    iter: Iter<T>,
+    size: usize,
+}
+impl<T: ?Sized> Iterator for Chunks<T> {
+    type Item = Vec<Arc<T>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunk: Vec<_> = self.iter.by_ref().take(self.size).collect();
+        if chunk.is_empty() {
+            None
+        } else {
+            Some(chunk)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.iter.len().div_ceil(self.size);
+        (len, Some(len))
+    }
+}
+impl<T: ?Sized> ExactSizeIterator for Chunks<T> {}
+
+/// An iterator over overlapping runs of elements, created with [`windows`].
+///
+/// [`windows`]: struct.Fral.html#method.windows
+pub struct Windows<T: ?Sized> {
+    iter: Iter<T>,
+    window: VecDeque<Arc<T>>,
+    size: usize,
+}
+impl<T: ?Sized> Iterator for Windows<T> {
+    type Item = Vec<Arc<T>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.window.len() == self.size {
+            self.window.pop_front();
+        }
+        while self.window.len() < self.size {
+            self.window.push_back(self.iter.next()?);
+        }
+        Some(self.window.iter().cloned().collect())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // a full window has already been yielded, so each further element makes one more.
+        let len = if self.window.len() == self.size {
+            self.iter.len()
+        } else {
+            (self.window.len() + self.iter.len() + 1).saturating_sub(self.size)
+        };
+        (len, Some(len))
+    }
+}
+impl<T: ?Sized> ExactSizeIterator for Windows<T> {}
+
+/// An iterator placing a separator between elements, created with [`iter_intersperse`].
+///
+/// [`iter_intersperse`]: struct.Fral.html#method.iter_intersperse
+pub struct Intersperse<T: ?Sized> {
+    iter: Peekable<Iter<T>>,
+    separator: Arc<T>,
+    separator_next: bool,
+}
+impl<T: ?Sized> Iterator for Intersperse<T> {
+    type Item = Arc<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.separator_next && self.iter.peek().is_some() {
+            self.separator_next = false;
+            Some(self.separator.clone())
+        } else {
+            self.separator_next = true;
+            self.iter.next()
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = match self.iter.len() {
+            0 => 0,
+            n if self.separator_next => 2 * n,
+            n => 2 * n - 1,
+        };
+        (len, Some(len))
+    }
+}
+impl<T: ?Sized> ExactSizeIterator for Intersperse<T> {}
+
+/// An iterator over arrays of `N` elements, created with [`array_chunks`].
+///
+/// [`array_chunks`]: struct.Fral.html#method.array_chunks
+pub struct ArrayChunks<T: ?Sized, const N: usize> {
+    iter: Iter<T>,
+    remainder: Vec<Arc<T>>,
+}
+impl<T: ?Sized, const N: usize> ArrayChunks<T, N> {
+    /// Get the elements left over after the last full array. This is empty until the iterator
+    /// has returned `None`.
+    pub fn remainder(&self) -> &[Arc<T>] {
+        &self.remainder
+    }
+}
+impl<T: ?Sized, const N: usize> Iterator for ArrayChunks<T, N> {
+    type Item = [Arc<T>; N];
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunk: Vec<_> = self.iter.by_ref().take(N).collect();
+        match <[Arc<T>; N]>::try_from(chunk) {
+            Ok(chunk) => Some(chunk),
+            Err(rest) => {
+                if !rest.is_empty() {
+                    self.remainder = rest;
+                }
+                None
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.iter.len() / N;
+        (len, Some(len))
+    }
+}
+impl<T: ?Sized, const N: usize> ExactSizeIterator for ArrayChunks<T, N> {}
+
+/// Automatic [`Arc`] wrapping.
+///
+/// Each type converts only to an `Arc` of itself, so the element type can be inferred from what
+/// is passed. Other conversions, such as from a `Box<T>` or a `&str` for a `Fral<str>`, go
+/// through [`Fral::cons_from`], and references are cloned with [`Fral::cons_cloned`].
+///
+/// [`Arc`]: https://doc.rust-lang.org/stable/std/sync/struct.Arc.html
+/// [`Fral::cons_from`]: struct.Fral.html#method.cons_from
+/// [`Fral::cons_cloned`]: struct.Fral.html#method.cons_cloned
+pub trait AsArc<T: ?Sized> {
+    #[allow(clippy::wrong_self_convention)]
+    fn as_arc(self) -> Arc<T>;
+}
+
+impl<T> AsArc<T> for T {
+    fn as_arc(self) -> Arc<T> {
+        Arc::from(self)
+    }
+}
+
+impl<T: ?Sized> AsArc<T> for Arc<T> {
+    fn as_arc(self) -> Arc<T> {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
     use super::Fral;
+    use super::Pair::Cons;
     use std::sync::Arc;
 
     #[test]
-    fn empty() {
-        let f: Fral<u8> = Fral::new();
-        assert_eq!(f, Fral::default());
-        assert!(f.is_empty());
-        assert_eq!(f.len(), 0);
+    fn empty() {
+        let f: Fral<u8> = Fral::new();
+        assert_eq!(f, Fral::<u8>::default());
+        assert!(f.is_empty());
+        assert_eq!(f.len(), 0);
+    }
+    #[test]
+    fn empty_does_not_allocate() {
+        const EMPTY: Fral<u8> = Fral::EMPTY;
+        assert_eq!(EMPTY.root_strong_count(), 0);
+        let f = EMPTY.cons(1).uncons().unwrap().1;
+        assert_eq!(f, EMPTY);
+        assert_eq!(f.root_strong_count(), 0);
+    }
+    #[test]
+    fn singleton() {
+        let f = Fral::new();
+        let f = f.cons(42);
+        assert_eq!(f.get(0), Some(Arc::new(42)));
+        assert_eq!(f.get(1), None);
+        if let Some((head, tail)) = f.uncons() {
+            assert_eq!(*head, 42);
+            assert!(tail.is_empty());
+        } else {
+            panic!("assertion failed: couldn't uncons");
+        }
+        assert_eq!(f.iter().collect::<Vec<_>>(), vec![Arc::new(42)]);
+    }
+    #[test]
+    fn many_items() {
+        let mut f = Fral::new();
+        for item in [1, 2, 3, 4, 5] {
+            f = f.cons(item);
+        }
+        assert_eq!(f.get(0), Some(Arc::new(5)));
+        assert_eq!(f.get(1), Some(Arc::new(4)));
+        assert_eq!(f.get(2), Some(Arc::new(3)));
+        assert_eq!(f.get(3), Some(Arc::new(2)));
+        assert_eq!(f.get(4), Some(Arc::new(1)));
+        assert_eq!(f.get(5), None);
+        if let Some((head, tail)) = f.uncons() {
+            assert_eq!(*head, 5);
+            assert_eq!(tail.len(), 4);
+        } else {
+            panic!("assertion failed: couldn't uncons");
+        }
+        assert_eq!(
+            f.iter().collect::<Vec<_>>(),
+            vec![
+                Arc::new(5),
+                Arc::new(4),
+                Arc::new(3),
+                Arc::new(2),
+                Arc::new(1),
+            ]
+        );
+    }
+    #[test]
+    fn well_formed() {
+        let mut f = Fral::new();
+        for item in 0..100 {
+            f = f.cons(item);
+            assert!(f.is_well_formed());
+        }
+        while let Some((_, tail)) = f.uncons() {
+            f = tail;
+            assert!(f.is_well_formed());
+        }
+    }
+    #[test]
+    fn strong_counts() {
+        let f: Fral<_> = (0..10).collect();
+        assert_eq!(f.root_strong_count(), 1);
+        assert_eq!(f.max_element_strong_count(), Some(1));
+        let g = f.clone();
+        assert_eq!(f.root_strong_count(), 2);
+        let x = g.get(3).unwrap();
+        assert_eq!(f.max_element_strong_count(), Some(2));
+        drop(x);
+        let _h = f.uncons().unwrap().1;
+        assert_eq!(f.max_element_strong_count(), Some(1));
+        let u = f.update(9, 100).unwrap();
+        assert_eq!(f.max_element_strong_count(), Some(2));
+        assert_eq!(u.max_element_strong_count(), Some(2));
+        drop(u);
+        assert_eq!(f.max_element_strong_count(), Some(1));
+        assert_eq!(Fral::<u8>::new().max_element_strong_count(), None);
+    }
+    #[cfg(feature = "rand")]
+    #[test]
+    fn shuffle() {
+        use rand::{SeedableRng, XorShiftRng};
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+        let f: Fral<_> = (0..50).collect();
+        let g = f.shuffle(&mut rng);
+        assert_eq!(g.len(), 50);
+        assert_ne!(g, f);
+        let mut items: Vec<_> = g.iter().map(|x| *x).collect();
+        items.sort();
+        assert_eq!(items, (0..50).collect::<Vec<_>>());
+        // elements are shared
+        assert_eq!(f.max_element_strong_count(), Some(2));
+    }
+    #[cfg(feature = "rand")]
+    #[test]
+    fn choose_and_sample() {
+        use rand::{SeedableRng, XorShiftRng};
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+        assert_eq!(Fral::<u8>::new().choose(&mut rng), None);
+        let f: Fral<_> = (0..50).collect();
+        assert!(*f.choose(&mut rng).unwrap() < 50);
+        let mut items: Vec<_> = f.sample(&mut rng, 10).into_iter().map(|x| *x).collect();
+        items.sort();
+        items.dedup();
+        assert_eq!(items.len(), 10);
+        assert_eq!(f.sample(&mut rng, 100).len(), 50);
+    }
+    #[test]
+    fn bulk_matches_cons() {
+        let items: Vec<_> = (0..100).map(Arc::new).collect();
+        for n in 0..items.len() {
+            let f: Fral<i32> = items[..n].iter().cloned().rev().collect();
+            let g = Fral::from_slice(&items[..n]);
+            assert!(g.is_well_formed());
+            assert_eq!(g, f);
+        }
+    }
+    #[test]
+    fn split_at_many() {
+        let f: Fral<_> = (0..40).rev().collect();
+        for &(a, b) in &[(0, 0), (0, 40), (3, 17), (7, 7), (15, 40), (40, 40)] {
+            let segments = f.split_at_many(&[a, b]);
+            assert_eq!(segments.len(), 3);
+            let mut i = 0;
+            for (segment, &len) in segments.iter().zip(&[a, b - a, 40 - b]) {
+                assert!(segment.is_well_formed());
+                assert_eq!(segment.len(), len);
+                for x in segment.iter() {
+                    assert_eq!(*x, i);
+                    i += 1;
+                }
+            }
+        }
+        assert_eq!(f.split_at_many(&[]), vec![f.clone()]);
+    }
+    #[test]
+    #[should_panic]
+    fn split_at_many_unsorted() {
+        let f: Fral<_> = (0..10).collect();
+        f.split_at_many(&[5, 2]);
+    }
+    #[test]
+    fn view() {
+        let f: Fral<_> = (0..20).rev().collect();
+        let view = f.view(5..15);
+        assert_eq!(view.len(), 10);
+        assert_eq!(view.get(0), Some(Arc::new(5)));
+        assert_eq!(view.get(9), Some(Arc::new(14)));
+        assert_eq!(view.get(10), None);
+        let sub = view.view(2..=4);
+        assert_eq!(sub.iter().map(|x| *x).collect::<Vec<_>>(), vec![7, 8, 9]);
+        assert_eq!(sub.to_fral(), f.split_at_many(&[7, 10])[1]);
+        assert!(f.view(20..).is_empty());
+        assert_eq!(f.view(..).len(), 20);
+    }
+    #[test]
+    #[should_panic]
+    fn view_out_of_bounds() {
+        let f: Fral<_> = (0..20).collect();
+        f.view(5..25);
+    }
+    #[test]
+    fn bounded() {
+        use super::{BoundedFral, CapacityError, OnFull};
+        let mut f = BoundedFral::new(4, OnFull::DropOldest);
+        for x in 0..20 {
+            f = f.cons(x).unwrap();
+            assert_eq!(f.len(), (x + 1).min(4));
+            assert_eq!(f.get(0), Some(Arc::new(x)));
+            assert!(f.fral.len() < 8);
+        }
+        assert_eq!(f.get(4), None);
+        assert_eq!(f.to_fral(), (16..20).collect());
+        let (head, tail) = f.uncons().unwrap();
+        assert_eq!(*head, 19);
+        assert_eq!(tail.len(), 3);
+        assert!(!tail.is_full());
+
+        let f = BoundedFral::new(2, OnFull::Reject);
+        let f = f.cons(0).unwrap().cons(1).unwrap();
+        assert_eq!(f.cons(2).unwrap_err(), CapacityError { capacity: 2 });
+        assert!(BoundedFral::new(0, OnFull::DropOldest)
+            .cons(0)
+            .unwrap()
+            .is_empty());
+    }
+    #[test]
+    fn bounded_huge_capacity() {
+        use super::{BoundedFral, OnFull};
+        let mut f = BoundedFral::new(usize::MAX, OnFull::Reject);
+        for x in 0..10 {
+            f = f.cons(x).unwrap();
+        }
+        assert_eq!(f.len(), 10);
+        assert!(!f.is_full());
+        assert_eq!(f.to_fral(), (0..10).collect());
+    }
+    #[test]
+    fn interned() {
+        use super::Interner;
+        let mut interner = Interner::new();
+        let f = Fral::from_iter_interned(vec![1, 2, 1, 2], &mut interner);
+        let g = Fral::new().cons(2).cons_interned(1, &mut interner);
+        assert_eq!(interner.stats().elements, 2);
+        assert!(Arc::ptr_eq(&f.get(0).unwrap(), &f.get(2).unwrap()));
+        assert!(Arc::ptr_eq(&f.get(1).unwrap(), &g.get(0).unwrap()));
+        // the trees of a second identical list are all shared
+        let stats = interner.stats();
+        let h = Fral::from_iter_interned(vec![1, 2, 1, 2], &mut interner);
+        assert_eq!(h, f);
+        assert_eq!(interner.stats().trees, stats.trees);
+        assert_eq!(interner.stats().misses, stats.misses);
+        interner.clear();
+        assert_eq!(interner.stats(), Default::default());
+    }
+    #[test]
+    fn array_chunks() {
+        let f: Fral<_> = (0..7).rev().collect();
+        let mut chunks = f.array_chunks::<3>();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks.next().map(|c| c.map(|x| *x)), Some([0, 1, 2]));
+        assert_eq!(chunks.next().map(|c| c.map(|x| *x)), Some([3, 4, 5]));
+        assert!(chunks.remainder().is_empty());
+        assert_eq!(chunks.next(), None);
+        assert_eq!(chunks.remainder(), &[Arc::new(6)]);
+        assert_eq!(chunks.next(), None);
+        assert_eq!(chunks.next(), None);
+        assert_eq!(chunks.remainder(), &[Arc::new(6)]);
+        assert_eq!(f.array_chunks::<7>().count(), 1);
+    }
+    #[test]
+    fn select_nth() {
+        let f: Fral<_> = (0..100).map(|x| (x * 37) % 100).collect();
+        for k in 0..100 {
+            assert_eq!(f.select_nth(k), Some(Arc::new(k)));
+        }
+        assert_eq!(f.select_nth(100), None);
+        assert_eq!(f.median(), Some(Arc::new(49)));
+        assert_eq!(Fral::<u8>::new().median(), None);
+    }
+    #[test]
+    fn select_nth_by() {
+        let f: Fral<_> = (0..100).map(|x| (x * 37) % 100).collect();
+        for k in 0..100 {
+            assert_eq!(f.select_nth_by(k, |a, b| b.cmp(a)), Some(Arc::new(99 - k)));
+        }
+        assert_eq!(f.select_nth_by(100, |a, b| a.cmp(b)), None);
+    }
+    #[test]
+    fn edits() {
+        use super::Edits;
+        let base: Fral<_> = (0..50).rev().collect();
+        let mut edits = Edits::new(&base);
+        assert!(edits.is_empty());
+        edits.remove(3);
+        edits.fill(10..13, 100);
+        edits.update(11, 200);
+        edits.update(3, 300);
+        edits.remove(20);
+        let f = edits.commit();
+        let mut expected: Vec<_> = (0..50).collect();
+        expected[3] = 300;
+        expected[10] = 100;
+        expected[11] = 200;
+        expected[12] = 100;
+        expected.remove(20);
+        assert_eq!(f.iter().map(|x| *x).collect::<Vec<_>>(), expected);
+        assert!(f.is_well_formed());
+        assert!(Arc::ptr_eq(&f.get(10).unwrap(), &f.get(12).unwrap()));
+        assert_eq!(base.len(), 50);
+        assert_eq!(Edits::new(&base).commit(), base);
+
+        let mut edits = Edits::new(&base);
+        edits.fill(.., 0);
+        assert_eq!(edits.commit().iter().filter(|x| **x == 0).count(), 50);
+    }
+    #[cfg(feature = "memchr")]
+    #[test]
+    fn byte_search() {
+        let mut bytes: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+        // straddles the first block boundary
+        bytes[4094..4098].copy_from_slice(&[255; 4]);
+        let f: Fral<u8> = bytes.iter().cloned().rev().collect();
+        assert_eq!(f.find_byte(250), Some(250));
+        assert_eq!(f.find_byte(255), Some(4094));
+        assert_eq!(Fral::<u8>::new().find_byte(0), None);
+        assert_eq!(f.find_subsequence(&[255; 4]), Some(4094));
+        assert_eq!(f.find_subsequence(&[249, 250, 0]), Some(249));
+        assert_eq!(f.find_subsequence(&[1, 1]), None);
+        assert_eq!(f.find_subsequence(&[]), Some(0));
+        let long: Vec<u8> = bytes.iter().cloned().chain(Some(0)).collect();
+        assert_eq!(f.find_subsequence(&long), None);
+    }
+    #[test]
+    fn heterogeneous_eq() {
+        let owned: Fral<String> = (0..20).map(|x| x.to_string()).collect();
+        let borrowed: Vec<String> = (0..20).map(|x| x.to_string()).collect();
+        let borrowed: Fral<&str> = borrowed.iter().map(|x| x.as_str()).collect();
+        assert_eq!(owned, borrowed);
+        assert_ne!(owned.uncons().unwrap().1, borrowed);
+        assert_ne!(owned.cons("x".to_string()), borrowed.cons("y"));
+        assert_eq!(Fral::<String>::new(), Fral::<&str>::new());
+    }
+    #[test]
+    fn trees() {
+        use super::TreeRef;
+        fn preorder(tree: TreeRef<usize>, out: &mut Vec<usize>) {
+            out.push(**tree.value());
+            if let (Some(left), Some(right)) = (tree.left(), tree.right()) {
+                assert_eq!(left.size(), right.size());
+                assert_eq!(tree.size(), 1 + 2 * left.size());
+                preorder(left, out);
+                preorder(right, out);
+            } else {
+                assert_eq!(tree.size(), 1);
+            }
+        }
+        for n in 0..40 {
+            let f: Fral<_> = (0..n).rev().collect();
+            let mut items = Vec::new();
+            for tree in f.trees() {
+                preorder(tree, &mut items);
+            }
+            assert_eq!(items, (0..n).collect::<Vec<_>>());
+        }
+    }
+    #[test]
+    fn iter_ids() {
+        let f: Fral<_> = (0..10).collect();
+        let g = f.uncons().unwrap().1.cons(100);
+        let ids: Vec<_> = f.iter_ids().collect();
+        let g_ids: Vec<_> = g.iter_ids().collect();
+        assert_eq!(ids[1..], g_ids[1..]);
+        assert_ne!(ids[0].1, g_ids[0].1);
+        assert_eq!(
+            ids.iter().map(|&(i, _)| i).collect::<Vec<_>>(),
+            (0..10).collect::<Vec<_>>()
+        );
+    }
+    #[cfg(feature = "async")]
+    #[test]
+    fn from_stream() {
+        use futures::executor::block_on;
+        use futures::stream::Stream;
+        use std::pin::Pin;
+        use std::task::{Context, Poll};
+
+        /// Yields `0..n`, returning `Pending` before each item.
+        struct Stutter(usize, usize, bool);
+        impl Stream for Stutter {
+            type Item = usize;
+            fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<usize>> {
+                self.2 = !self.2;
+                if self.2 {
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                } else if self.0 < self.1 {
+                    self.0 += 1;
+                    Poll::Ready(Some(self.0 - 1))
+                } else {
+                    Poll::Ready(None)
+                }
+            }
+        }
+        let items = || Stutter(0, 100, false);
+        let f: Fral<_> = block_on(Fral::from_stream(items()));
+        assert_eq!(f, (0..100).collect::<Fral<_>>());
+        let g: Fral<_> = block_on(Fral::from_stream_ordered(items()));
+        assert!(g.is_well_formed());
+        assert_eq!(g, (0..100).rev().collect::<Fral<_>>());
+    }
+    #[test]
+    fn update() {
+        for n in 0..40 {
+            let f: Fral<_> = (0..n).rev().collect();
+            for i in 0..n {
+                let g = f.update(i, 100).unwrap();
+                assert!(g.is_well_formed());
+                let mut expected: Vec<_> = (0..n).collect();
+                expected[i] = 100;
+                assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), expected);
+            }
+            assert_eq!(f.update(n, 100), None);
+            assert_eq!(
+                f.iter().map(|x| *x).collect::<Vec<_>>(),
+                (0..n).collect::<Vec<_>>()
+            );
+        }
+    }
+    #[test]
+    fn update_with() {
+        let f: Fral<_> = (0..20).rev().collect();
+        for i in 0..20 {
+            let g = f.update_with(i, |x| x + 100).unwrap();
+            assert_eq!(g.get(i), Some(Arc::new(i + 100)));
+            assert_eq!(g.iter().filter(|x| **x >= 100).count(), 1);
+        }
+        assert_eq!(f.update_with(20, |x| x + 100), None);
+    }
+    #[test]
+    fn apply_updates() {
+        let f: Fral<_> = (0..100).rev().collect();
+        let updates: Vec<_> = (0..500).map(|i| ((i * 7) % 100, i)).collect();
+        let g = f.apply_updates(updates.iter().cloned());
+        let mut expected: Vec<_> = (0..100).collect();
+        let mut h = f.clone();
+        for &(index, x) in &updates {
+            expected[index] = x;
+            h = h.update(index, x).unwrap();
+        }
+        assert!(g.is_well_formed());
+        assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), expected);
+        assert_eq!(g, h);
+        assert_eq!(f.apply_updates(Vec::<(usize, usize)>::new()), f);
+    }
+    #[test]
+    fn remove() {
+        for n in 0..40 {
+            let f: Fral<_> = (0..n).rev().collect();
+            for i in 0..n {
+                let (x, g) = f.remove(i).unwrap();
+                assert_eq!(*x, i);
+                assert!(g.is_well_formed());
+                let mut expected: Vec<_> = (0..n).collect();
+                expected.remove(i);
+                assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), expected);
+            }
+            assert_eq!(f.remove(n), None);
+        }
+    }
+    #[test]
+    fn swap() {
+        let n = 20;
+        let f: Fral<_> = (0..n).rev().collect();
+        for i in 0..n {
+            for j in 0..n {
+                let g = f.swap(i, j).unwrap();
+                assert!(g.is_well_formed());
+                let mut expected: Vec<_> = (0..n).collect();
+                expected.swap(i, j);
+                assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), expected);
+            }
+            assert_eq!(f.swap(i, n), None);
+            assert_eq!(f.swap(n, i), None);
+        }
+    }
+    #[test]
+    fn first_and_last() {
+        for n in 1..40 {
+            let f: Fral<_> = (0..n).rev().collect();
+            assert_eq!(f.first(), Some(Arc::new(0)));
+            assert_eq!(f.last(), Some(Arc::new(n - 1)));
+        }
+        assert_eq!(Fral::<u8>::new().first(), None);
+    }
+    #[test]
+    fn tail() {
+        let mut f: Fral<_> = (0..20).rev().collect();
+        for n in (0..20).rev() {
+            let g = f.tail().unwrap();
+            assert!(g.is_well_formed());
+            assert_eq!(g.len(), n);
+            assert_eq!(Some(g.clone()), f.uncons().map(|(_, g)| g));
+            f = g;
+        }
+        assert_eq!(f.tail(), None);
+    }
+    #[test]
+    fn split_at() {
+        for n in 0..40 {
+            let f: Fral<_> = (0..n).rev().collect();
+            for i in 0..=n {
+                let (front, back) = f.split_at(i);
+                assert!(front.is_well_formed() && back.is_well_formed());
+                assert_eq!(
+                    front.iter().map(|x| *x).collect::<Vec<_>>(),
+                    (0..i).collect::<Vec<_>>()
+                );
+                assert_eq!(
+                    back.iter().map(|x| *x).collect::<Vec<_>>(),
+                    (i..n).collect::<Vec<_>>()
+                );
+            }
+        }
+    }
+    #[test]
+    #[should_panic]
+    fn split_at_out_of_bounds() {
+        let f: Fral<_> = (0..10).collect();
+        f.split_at(11);
+    }
+    #[test]
+    fn take() {
+        for n in 0..40 {
+            let f: Fral<_> = (0..n).rev().collect();
+            for k in 0..n + 2 {
+                let g = f.take(k);
+                assert!(g.is_well_formed());
+                let expected: Vec<_> = (0..k.min(n)).collect();
+                assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), expected);
+            }
+        }
+        // the trees have sizes 1, 1, 3, and the first also leads 2 = 1 + 1 and 4 = 1 + 3.
+        let f: Fral<_> = (0..5).collect();
+        for &k in &[2, 4] {
+            let g = f.take(k);
+            match (f.pair(), g.pair()) {
+                (&Cons((_, ref t1), _), &Cons((_, ref t2), _)) => assert!(Arc::ptr_eq(t1, t2)),
+                _ => unreachable!(),
+            }
+        }
+    }
+    #[test]
+    fn drop_front() {
+        for n in 0..40 {
+            let f: Fral<_> = (0..n).rev().collect();
+            for k in 0..n + 2 {
+                let g = f.drop_front(k);
+                assert!(g.is_well_formed());
+                let expected: Vec<_> = (k.min(n)..n).collect();
+                assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), expected);
+            }
+        }
+    }
+    #[test]
+    fn append() {
+        for n in 0..20 {
+            for m in 0..20 {
+                let f: Fral<_> = (0..n).rev().collect();
+                let g: Fral<_> = (n..n + m).rev().collect();
+                let h = f.append(&g);
+                assert!(h.is_well_formed());
+                let expected: Vec<_> = (0..n + m).collect();
+                assert_eq!(h.iter().map(|x| *x).collect::<Vec<_>>(), expected);
+            }
+        }
+    }
+    #[test]
+    fn add() {
+        let f: Fral<_> = (0..3).rev().collect();
+        let g: Fral<_> = (3..5).rev().collect();
+        let expected: Vec<_> = (0..5).collect();
+        assert_eq!((&f + &g).iter().map(|x| *x).collect::<Vec<_>>(), expected);
+        assert_eq!(f.clone() + &g, &f + &g);
+        assert_eq!(f.clone() + g.clone(), &f + &g);
+    }
+    #[test]
+    fn reverse() {
+        for n in 0..40 {
+            let f: Fral<_> = (0..n).collect();
+            let g = f.reverse();
+            assert!(g.is_well_formed());
+            assert_eq!(g, (0..n).rev().collect::<Fral<_>>());
+            assert_eq!(g.reverse(), f);
+        }
+    }
+    #[test]
+    fn map() {
+        for n in 0..40 {
+            let f: Fral<_> = (0..n).rev().collect();
+            let g = f.map(|x| 2 * x);
+            assert!(g.is_well_formed());
+            assert_eq!(g, (0..n).rev().map(|x| 2 * x).collect::<Fral<_>>());
+        }
+    }
+    #[test]
+    fn filter() {
+        let f: Fral<_> = (0..50).rev().collect();
+        let g = f.filter(|x| x % 3 == 0);
+        assert!(g.is_well_formed());
+        assert_eq!(g, (0..50).rev().filter(|x| x % 3 == 0).collect::<Fral<_>>());
+        assert!(Arc::ptr_eq(&g.get(1).unwrap(), &f.get(3).unwrap()));
+        let h = f.filter_map(|x| if x % 3 == 0 { Some(x / 3) } else { None });
+        assert!(h.is_well_formed());
+        assert_eq!(h, (0..17).rev().collect::<Fral<_>>());
+    }
+    #[test]
+    fn flat_map() {
+        let f: Fral<_> = (0..10).rev().collect();
+        let g = f.flat_map(|&x| 0..x);
+        assert!(g.is_well_formed());
+        let expected: Vec<_> = (0..10).flat_map(|x| 0..x).collect();
+        assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), expected);
+        assert!(f.flat_map(|_| None::<u8>).is_empty());
+    }
+    #[test]
+    fn zip() {
+        let f: Fral<_> = (0..10).rev().collect();
+        let g: Fral<_> = (0..15).rev().map(|x| x * 10).collect();
+        let zipped = f.zip(&g);
+        assert!(zipped.is_well_formed());
+        assert_eq!(zipped.len(), 10);
+        for (i, pair) in zipped.iter().enumerate() {
+            assert_eq!((*pair.0, *pair.1), (i, i * 10));
+        }
+        assert_eq!(
+            g.zip_with(&f, |x, y| x + y),
+            (0..10).rev().map(|x| x * 11).collect()
+        );
+        assert!(f.zip(&Fral::<u8>::new()).is_empty());
+    }
+    #[test]
+    fn unzip() {
+        let f: Fral<_> = (0..20).rev().map(|x| (x, x.to_string())).collect();
+        let (numbers, strings) = f.unzip();
+        assert!(numbers.is_well_formed() && strings.is_well_formed());
+        assert_eq!(numbers, (0..20).rev().collect::<Fral<_>>());
+        assert_eq!(
+            strings,
+            (0..20).rev().map(|x| x.to_string()).collect::<Fral<_>>()
+        );
+    }
+    #[test]
+    fn flatten() {
+        let lists: Fral<_> = (0..8)
+            .rev()
+            .map(|n| (0..n).rev().collect::<Fral<_>>())
+            .collect();
+        let f = lists.flatten();
+        assert!(f.is_well_formed());
+        let expected: Vec<_> = (0..8).flat_map(|n| 0..n).collect();
+        assert_eq!(f.iter().map(|x| *x).collect::<Vec<_>>(), expected);
+        assert_eq!(f.drop_front(21), *lists.last().unwrap());
+        assert!(Fral::<Fral<u8>>::new().flatten().is_empty());
+    }
+    #[test]
+    fn partition() {
+        let f: Fral<_> = (0..50).rev().collect();
+        let (small, large) = f.partition(|&x| x < 20);
+        assert!(small.is_well_formed() && large.is_well_formed());
+        assert_eq!(small, f.take(20));
+        assert_eq!(large, f.drop_front(20));
+    }
+    #[test]
+    fn dedup() {
+        let items = [0, 0, 1, 2, 2, 2, 3, 0, 4, 5, 6, 7, 8, 9];
+        let f: Fral<_> = items.iter().cloned().rev().collect();
+        let g = f.dedup();
+        assert!(g.is_well_formed());
+        let mut expected = items.to_vec();
+        expected.dedup();
+        assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), expected);
+        assert_eq!(g.drop_front(5), f.drop_front(8));
+        let h: Fral<_> = (0..10).collect();
+        assert_eq!(h.dedup(), h);
+        assert_eq!(h.dedup_by(|_, _| true).len(), 1);
+    }
+    #[test]
+    fn chunks() {
+        let f: Fral<_> = (0..10).rev().collect();
+        for size in 1..12 {
+            let items: Vec<_> = (0..10).collect();
+            let chunks = f.chunks(size);
+            assert_eq!(chunks.len(), items.chunks(size).len());
+            let chunks: Vec<Vec<_>> = chunks.map(|c| c.iter().map(|x| **x).collect()).collect();
+            assert_eq!(chunks, items.chunks(size).collect::<Vec<_>>());
+        }
+        assert_eq!(Fral::<u8>::new().chunks(3).next(), None);
+    }
+    #[test]
+    fn windows() {
+        let items: Vec<_> = (0..10).collect();
+        let f: Fral<_> = items.iter().cloned().rev().collect();
+        for size in 1..12 {
+            let mut windows = f.windows(size);
+            let mut expected = items.windows(size);
+            loop {
+                assert_eq!(windows.len(), expected.len());
+                match (windows.next(), expected.next()) {
+                    (Some(w), Some(e)) => assert_eq!(w.iter().map(|x| **x).collect::<Vec<_>>(), e),
+                    (None, None) => break,
+                    _ => panic!("window counts differ"),
+                }
+            }
+            assert_eq!(windows.len(), 0);
+        }
+    }
+    #[test]
+    fn intersperse() {
+        for n in 0..20usize {
+            let f: Fral<_> = (1..=n).rev().collect();
+            let mut iter = f.iter_intersperse(0);
+            let mut items = Vec::new();
+            loop {
+                let len = iter.len();
+                match iter.next() {
+                    Some(x) => items.push(*x),
+                    None => break,
+                }
+                assert_eq!(iter.len(), len - 1);
+            }
+            assert_eq!(items.len(), (2 * n).saturating_sub(1));
+            for (i, &x) in items.iter().enumerate() {
+                assert_eq!(x, if i % 2 == 0 { i / 2 + 1 } else { 0 });
+            }
+            let g = f.intersperse(0);
+            assert!(g.is_well_formed());
+            assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), items);
+        }
+        let f: Fral<_> = (0..3).collect();
+        let g = f.intersperse(10);
+        assert!(Arc::ptr_eq(&g.get(1).unwrap(), &g.get(3).unwrap()));
+    }
+    #[test]
+    fn scan() {
+        for n in 0..40 {
+            let f: Fral<_> = (0..n).rev().collect();
+            let g = f.scan(0, |acc, x| acc + x);
+            assert!(g.is_well_formed());
+            let expected: Vec<_> = (0..n).map(|i| i * (i + 1) / 2).collect();
+            assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), expected);
+        }
+    }
+    #[test]
+    fn sorted() {
+        let items: Vec<_> = (0..50).map(|x| (x * 37) % 50).collect();
+        let f: Fral<_> = items.iter().cloned().rev().collect();
+        let g = f.sorted();
+        assert!(g.is_well_formed());
+        assert_eq!(g, (0..50).rev().collect::<Fral<_>>());
+        // stable: equal keys keep their order
+        let h = f.sorted_by_key(|x| x % 2);
+        let mut expected = items.clone();
+        expected.sort_by_key(|x| x % 2);
+        assert_eq!(h.iter().map(|x| *x).collect::<Vec<_>>(), expected);
+        assert!(Arc::ptr_eq(&g.get(0).unwrap(), &f.get(0).unwrap()));
+    }
+    #[test]
+    fn binary_search() {
+        for n in 0..30 {
+            let items: Vec<_> = (0..n).map(|x| 2 * x).collect();
+            let f: Fral<_> = items.iter().cloned().rev().collect();
+            for x in 0..2 * n + 1 {
+                assert_eq!(f.binary_search(&x), items.binary_search(&x));
+            }
+        }
+    }
+    #[test]
+    fn is_sorted() {
+        for n in 0..30 {
+            let f: Fral<_> = (0..n).rev().collect();
+            assert!(f.is_sorted());
+            for i in 0..n {
+                let g = f.update(i, 100).unwrap();
+                assert_eq!(g.is_sorted(), i + 1 == n);
+            }
+        }
+    }
+    #[test]
+    fn contains() {
+        let f: Fral<_> = (0..30).map(|x| x.to_string()).collect();
+        assert!((0..30).all(|x| f.contains(&x.to_string())));
+        assert!(!f.contains(&"30".to_string()));
+        assert!(!Fral::<u8>::new().contains(&0));
+    }
+    #[test]
+    fn position() {
+        let f: Fral<usize> = (0..50).map(|x| x % 7).rev().collect();
+        for x in 0..7 {
+            assert_eq!(f.index_of(&x), Some(x));
+        }
+        assert_eq!(f.index_of(&7), None);
+        assert_eq!(f.position(|x| *x > 10), None);
+        let g = f.update(30, 100).unwrap();
+        assert_eq!(g.position(|x| *x > 6), Some(30));
+        assert_eq!(Fral::<u8>::new().position(|_| true), None);
+    }
+    #[test]
+    fn rposition() {
+        let f: Fral<usize> = (0..50).map(|x| x % 7).rev().collect();
+        for x in 0..7 {
+            assert_eq!(f.rposition(|y| *y == x), Some(49 - (49 - x) % 7));
+        }
+        let mut visited = 0;
+        assert_eq!(
+            f.rposition(|x| {
+                visited += 1;
+                *x == 5
+            }),
+            Some(47)
+        );
+        assert_eq!(visited, 3);
+        assert_eq!(f.rposition(|x| *x > 10), None);
+        assert_eq!(Fral::<u8>::new().rposition(|_| true), None);
+    }
+    #[test]
+    fn prefixes_and_suffixes() {
+        let f: Fral<usize> = (0..40).rev().collect();
+        for n in 0..=40 {
+            let g: Fral<usize> = (0..n).rev().collect();
+            let h: Fral<usize> = (40 - n..40).rev().collect();
+            assert!(f.starts_with(&g));
+            assert!(f.ends_with(&h));
+            assert_eq!(f.strip_prefix(&g), Some(f.drop_front(n)));
+            assert_eq!(f.starts_with(&h), n == 0 || n == 40);
+            assert_eq!(f.ends_with(&g), n == 0 || n == 40);
+        }
+        let g: Fral<usize> = (0..41).rev().collect();
+        assert!(!f.starts_with(&g) && !f.ends_with(&g));
+        assert!(f.strip_prefix(&g).is_none());
+
+        // shared trees, and a mismatch hidden inside a tree of the same size.
+        let longer = f.cons(100).cons(200);
+        assert!(longer.ends_with(&f));
+        assert!(longer.starts_with(&longer.take(25)));
+        let changed = f.update(35, 0).unwrap();
+        assert!(!longer.ends_with(&changed));
+        assert!(!f.starts_with(&changed.take(36)));
+    }
+    #[test]
+    fn splice() {
+        let f: Fral<usize> = (0..30).rev().collect();
+        for &(start, end) in &[(0, 0), (0, 30), (3, 17), (29, 30), (30, 30), (12, 12)] {
+            for m in 0..4 {
+                let g = f.splice(start..end, (100..100 + m).map(Arc::new));
+                assert!(g.is_well_formed());
+                let mut expected: Vec<usize> = (0..30).collect();
+                expected.splice(start..end, 100..100 + m);
+                assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), expected);
+            }
+        }
+        // the elements after the range are shared.
+        let g = f.splice(..4, vec![7]);
+        assert!(Arc::ptr_eq(&g.get(26).unwrap(), &f.get(29).unwrap()));
     }
     #[test]
-    fn singleton() {
-        let f = Fral::new();
-        let f = f.cons(42);
-        assert_eq!(f.get(0), Some(Arc::new(42)));
-        assert_eq!(f.get(1), None);
-        if let Some((head, tail)) = f.uncons() {
-            assert_eq!(*head, 42);
-            assert!(tail.is_empty());
-        } else {
-            panic!("assertion failed: couldn't uncons");
+    fn slice() {
+        let f: Fral<usize> = (0..40).rev().collect();
+        for start in 0..=40 {
+            for end in start..=40 {
+                let g = f.slice(start..end);
+                assert!(g.is_well_formed());
+                assert_eq!(
+                    g.iter().map(|x| *x).collect::<Vec<_>>(),
+                    (start..end).collect::<Vec<_>>()
+                );
+            }
         }
-        assert_eq!(f.iter().collect::<Vec<_>>(), vec![Arc::new(42)]);
+        // leading trees in the decomposition of the slice's length are kept.
+        let first_tree = |f: &Fral<usize>| match *f.pair() {
+            Cons((_, ref tree), _) => tree.clone(),
+            _ => unreachable!(),
+        };
+        assert!(Arc::ptr_eq(&first_tree(&f), &first_tree(&f.slice(..39))));
     }
     #[test]
-    fn many_items() {
+    fn rotate() {
+        let f: Fral<usize> = (0..20).rev().collect();
+        for n in 0..=20 {
+            let mut expected: Vec<usize> = (0..20).collect();
+            expected.rotate_left(n);
+            let g = f.rotate_left(n);
+            assert!(g.is_well_formed());
+            assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), expected);
+            assert_eq!(g.rotate_right(n), f);
+        }
+    }
+    #[test]
+    #[should_panic]
+    fn rotate_out_of_bounds() {
+        let f: Fral<usize> = (0..20).collect();
+        f.rotate_right(21);
+    }
+    #[test]
+    fn repeat() {
+        for n in 0..40 {
+            let f = Fral::repeat("x", n);
+            assert!(f.is_well_formed());
+            assert_eq!(f.len(), n);
+            assert!(f.iter().all(|x| *x == "x"));
+        }
+        let x = Arc::new(5);
+        let f = Fral::repeat(x.clone(), 1_000_000);
+        assert!(f.iter().all(|y| Arc::ptr_eq(&x, &y)));
+        // one reference from each distinct node, and one from `x` itself.
+        assert_eq!(Arc::strong_count(&x), 20);
+        let g = f.update(500_000, 6).unwrap();
+        assert_eq!(g.iter().filter(|y| **y == 6).count(), 1);
+    }
+    #[test]
+    fn unfold() {
+        let fib: Fral<u64> = Fral::unfold((0, 1), |(a, b)| {
+            if a > 1000 {
+                None
+            } else {
+                Some((a, (b, a + b)))
+            }
+        });
+        assert!(fib.is_well_formed());
+        assert_eq!(fib.len(), 17);
+        assert_eq!(*fib.last().unwrap(), 987);
+        let empty: Fral<u8> = Fral::unfold((), |_| None::<(u8, ())>);
+        assert!(empty.is_empty());
+    }
+    #[test]
+    fn extend_front() {
+        for n in 0..20 {
+            let f: Fral<usize> = (n..20).rev().collect();
+            for m in 0..=n {
+                let g = f.extend_front(n - m..n);
+                assert!(g.is_well_formed());
+                assert_eq!(g, (n - m..20).rev().collect::<Fral<usize>>());
+            }
+        }
+        let f: Fral<usize> = (0..20).rev().collect();
+        assert!(Arc::ptr_eq(
+            f.pair.as_ref().unwrap(),
+            f.extend_front(Vec::<usize>::new()).pair.as_ref().unwrap()
+        ));
+    }
+    #[test]
+    fn push_back() {
         let mut f = Fral::new();
-        for item in vec![1, 2, 3, 4, 5] {
-            f = f.cons(item);
+        for x in 0..50usize {
+            f = f.push_back(x);
+            assert!(f.is_well_formed());
+            assert_eq!(f, (0..=x).rev().collect::<Fral<usize>>());
         }
-        assert_eq!(f.get(0), Some(Arc::new(5)));
-        assert_eq!(f.get(1), Some(Arc::new(4)));
-        assert_eq!(f.get(2), Some(Arc::new(3)));
-        assert_eq!(f.get(3), Some(Arc::new(2)));
-        assert_eq!(f.get(4), Some(Arc::new(1)));
-        assert_eq!(f.get(5), None);
-        if let Some((head, tail)) = f.uncons() {
-            assert_eq!(*head, 5);
-            assert_eq!(tail.len(), 4);
-        } else {
-            panic!("assertion failed: couldn't uncons");
+    }
+    #[test]
+    fn pop_back() {
+        let mut f: Fral<usize> = (0..50).rev().collect();
+        for x in (0..50).rev() {
+            let (last, init) = f.pop_back().unwrap();
+            assert_eq!(*last, x);
+            assert!(init.is_well_formed());
+            assert_eq!(init.push_back(last), f);
+            f = init;
+        }
+        assert!(f.pop_back().is_none());
+    }
+    #[test]
+    fn get_many() {
+        let f: Fral<usize> = (0..100).rev().collect();
+        let indices: Vec<usize> = (0..150).map(|i| (i * 37) % 120).collect();
+        let found = f.get_many(&indices);
+        for (&index, x) in indices.iter().zip(found) {
+            assert_eq!(x, f.get(index));
+        }
+        assert!(f.get_many(&[]).is_empty());
+        assert_eq!(Fral::<u8>::new().get_many(&[0, 1]), vec![None, None]);
+    }
+    #[test]
+    fn merge() {
+        let f: Fral<usize> = (0..30).map(|x| 3 * x).rev().collect();
+        let g: Fral<usize> = (0..20).map(|x| 2 * x).rev().collect();
+        let merged = f.merge(&g);
+        assert!(merged.is_well_formed());
+        let mut expected: Vec<usize> = f.iter().chain(g.iter()).map(|x| *x).collect();
+        expected.sort();
+        assert_eq!(merged.iter().map(|x| *x).collect::<Vec<_>>(), expected);
+        // the rest of `f` is shared, and ties take from the left first.
+        assert!(Arc::ptr_eq(&merged.last().unwrap(), &f.last().unwrap()));
+        let ones: Fral<usize> = Fral::repeat(1, 3);
+        let merged = ones.merge(&Fral::repeat(1, 2));
+        assert!(Arc::ptr_eq(
+            &merged.first().unwrap(),
+            &ones.first().unwrap()
+        ));
+        assert_eq!(f.merge(&Fral::new()), f);
+    }
+    #[test]
+    fn group_by() {
+        let f: Fral<usize> = (0..40).map(|x| x / 3).rev().collect();
+        let groups = f.group_by(|x| *x);
+        assert!(groups.is_well_formed());
+        assert_eq!(groups.len(), 14);
+        for (key, group) in groups.iter().enumerate() {
+            assert!(group.is_well_formed());
+            assert!(group.iter().all(|x| *x == key));
+        }
+        assert_eq!(groups.flatten(), f);
+        assert!(Fral::<u8>::new().group_by(|x| *x).is_empty());
+    }
+    #[test]
+    fn join() {
+        let sep: Fral<usize> = vec![100, 101].into_iter().rev().collect();
+        for k in 0..6 {
+            let nested: Fral<Fral<usize>> = (0..k)
+                .map(|i| (10 * i..10 * i + i).rev().collect::<Fral<usize>>())
+                .rev()
+                .collect();
+            let joined = nested.join(&sep);
+            assert!(joined.is_well_formed());
+            let mut expected = Vec::new();
+            for i in 0..k {
+                if i > 0 {
+                    expected.extend(vec![100, 101]);
+                }
+                expected.extend(10 * i..10 * i + i);
+            }
+            assert_eq!(joined.iter().map(|x| *x).collect::<Vec<_>>(), expected);
+        }
+    }
+    #[test]
+    fn concat_all() {
+        let lists: Vec<Fral<usize>> = (0..8)
+            .map(|i| ((i * i - i) / 2..(i * i + i) / 2).rev().collect())
+            .collect();
+        let all = Fral::concat_all(lists.clone());
+        assert!(all.is_well_formed());
+        assert_eq!(all, (0..28).rev().collect::<Fral<usize>>());
+        // the last nonempty list, a single tree of 7, becomes a subtree of the result.
+        use super::Tree::Node;
+        let mut with_empty = lists.clone();
+        with_empty.push(Fral::new());
+        let all = Fral::concat_all(with_empty);
+        let last_tree = |f: &Fral<usize>| {
+            let mut pair = f.pair();
+            while let Cons(_, ref rest) = *pair {
+                match **rest {
+                    Cons(..) => pair = rest,
+                    _ => break,
+                }
+            }
+            match *pair {
+                Cons((_, ref tree), _) => tree.clone(),
+                _ => unreachable!(),
+            }
+        };
+        match *last_tree(&all) {
+            Node(_, _, ref right) => assert!(Arc::ptr_eq(right, &last_tree(&lists[7]))),
+            _ => unreachable!(),
+        }
+        assert!(Fral::<u8>::concat_all(vec![]).is_empty());
+    }
+    #[test]
+    fn take_and_skip_while() {
+        let f: Fral<usize> = (0..30).rev().collect();
+        for n in 0..=31 {
+            let front = f.take_while(|x| *x < n);
+            let back = f.skip_while(|x| *x < n);
+            assert!(front.is_well_formed() && back.is_well_formed());
+            assert_eq!(front, f.take(n));
+            assert_eq!(back, f.drop_front(n));
+            assert_eq!(f.span(|x| *x < n), (front.clone(), back.clone()));
+            assert_eq!(f.split_when(|x| *x >= n), (front, back));
+        }
+    }
+    #[test]
+    fn resize() {
+        let f: Fral<usize> = (0..10).rev().collect();
+        let pad = Arc::new(100);
+        for n in 0..30 {
+            let g = f.resize(n, pad.clone());
+            assert!(g.is_well_formed());
+            assert_eq!(g.len(), n);
+            assert_eq!(g.take(10), f.take(n));
+            assert!(g.iter().skip(10).all(|x| Arc::ptr_eq(&x, &pad)));
+            assert_eq!(f.pad_to(n, pad.clone()).len(), n.max(10));
+        }
+    }
+    #[test]
+    fn swap_remove() {
+        let f: Fral<usize> = (0..20).rev().collect();
+        for i in 0..20 {
+            let (x, g) = f.swap_remove(i).unwrap();
+            assert_eq!(*x, i);
+            assert!(g.is_well_formed());
+            let mut expected: Vec<usize> = (0..20).collect();
+            expected.swap(0, i);
+            expected.remove(0);
+            assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), expected);
+        }
+        assert_eq!(f.swap_remove(20), None);
+    }
+    #[test]
+    fn try_map() {
+        use std::cell::Cell;
+        let f: Fral<usize> = (0..30).rev().collect();
+        let g = f.try_map(|x| Ok::<_, ()>(x * 2)).unwrap();
+        assert!(g.is_well_formed());
+        assert_eq!(g, f.map(|x| x * 2));
+        let calls = Cell::new(0);
+        let result = f.try_map(|x| {
+            calls.set(calls.get() + 1);
+            if *x < 10 {
+                Ok(*x)
+            } else {
+                Err(*x)
+            }
+        });
+        assert_eq!(result, Err(10));
+        assert_eq!(calls.get(), 11);
+    }
+    #[test]
+    fn map_while() {
+        let f: Fral<usize> = (0..30).rev().collect();
+        for n in 0..=31 {
+            let g = f.map_while(|x| if *x < n { Some(x * 2) } else { None });
+            assert!(g.is_well_formed());
+            assert_eq!(g, f.take(n).map(|x| x * 2));
+        }
+    }
+    #[test]
+    fn step_by() {
+        let f: Fral<usize> = (0..50).rev().collect();
+        for step in 1..60 {
+            let g = f.step_by(step);
+            assert!(g.is_well_formed());
+            assert_eq!(
+                g.iter().map(|x| *x).collect::<Vec<_>>(),
+                (0..50).step_by(step).collect::<Vec<_>>()
+            );
+        }
+        assert!(Fral::<u8>::new().step_by(3).is_empty());
+    }
+    #[test]
+    fn unique() {
+        let f: Fral<usize> = (0..60).map(|x| (x * x) % 17).rev().collect();
+        let g = f.unique();
+        assert!(g.is_well_formed());
+        let mut seen = Vec::new();
+        for x in f.iter() {
+            if !seen.contains(&*x) {
+                seen.push(*x);
+            }
+        }
+        assert_eq!(g.iter().map(|x| *x).collect::<Vec<_>>(), seen);
+        let h: Fral<usize> = (0..20).collect();
+        assert!(Arc::ptr_eq(
+            h.pair.as_ref().unwrap(),
+            h.unique().pair.as_ref().unwrap()
+        ));
+    }
+    #[test]
+    fn rget() {
+        let f: Fral<usize> = (0..30).rev().collect();
+        for i in 0..30 {
+            assert_eq!(f.rget(i), Some(Arc::new(29 - i)));
+        }
+        assert_eq!(f.rget(30), None);
+        assert_eq!(f.rget(usize::MAX), None);
+        assert_eq!(Fral::<u8>::new().rget(0), None);
+    }
+    #[test]
+    fn interleave() {
+        for n in 0..12 {
+            for m in 0..12 {
+                let f: Fral<usize> = (0..n).map(|x| 2 * x).rev().collect();
+                let g: Fral<usize> = (0..m).map(|x| 2 * x + 1).rev().collect();
+                let h = f.interleave(&g);
+                assert!(h.is_well_formed());
+                let mut expected: Vec<usize> = (0..2 * n.min(m)).collect();
+                expected.extend((n.min(m)..n).map(|x| 2 * x));
+                expected.extend((n.min(m)..m).map(|x| 2 * x + 1));
+                assert_eq!(h.iter().map(|x| *x).collect::<Vec<_>>(), expected);
+            }
+        }
+    }
+    #[test]
+    fn get_ref() {
+        let f: Fral<String> = (0..30).map(|x| x.to_string()).rev().collect();
+        for i in 0..30 {
+            assert_eq!(f.get_ref(i), Some(&i.to_string()));
+            assert!(std::ptr::eq(f.get_ref(i).unwrap(), &*f.get(i).unwrap()));
+        }
+        assert_eq!(f.get_ref(30), None);
+        assert_eq!(f.get_cloned(12), Some("12".to_string()));
+        assert_eq!(f.get_cloned(30), None);
+        assert_eq!(Fral::<u8>::new().get_ref(0), None);
+    }
+    #[test]
+    fn index() {
+        let f: Fral<usize> = (0..30).rev().collect();
+        assert!((0..30).all(|i| f[i] == i));
+        assert!((0..30).all(|i| unsafe { *f.get_unchecked(i) } == i));
+    }
+    #[test]
+    #[should_panic(expected = "index 30 out of bounds for list of length 30")]
+    fn index_out_of_bounds() {
+        let f: Fral<usize> = (0..30).rev().collect();
+        let _ = f[30];
+    }
+    #[test]
+    fn try_methods() {
+        use super::IndexOutOfBounds;
+        let f: Fral<usize> = (0..10).rev().collect();
+        let err = |index| IndexOutOfBounds { index, len: 10 };
+        assert_eq!(f.try_get(9), Ok(Arc::new(9)));
+        assert_eq!(f.try_get(10), Err(err(10)));
+        assert_eq!(f.try_update(3, 30), Ok(f.update(3, 30).unwrap()));
+        assert_eq!(f.try_update(10, 0), Err(err(10)));
+        assert_eq!(f.try_remove(3), Ok(f.remove(3).unwrap()));
+        assert_eq!(f.try_remove(10).unwrap_err(), err(10));
+        assert_eq!(f.try_split_at(10), Ok(f.split_at(10)));
+        assert_eq!(f.try_split_at(11), Err(err(11)));
+    }
+    #[test]
+    fn get_mut() {
+        let mut f: Fral<usize> = (0..30).rev().collect();
+        let snapshot = f.clone();
+        for i in 0..30 {
+            *f.get_mut(i).unwrap() += 100;
+        }
+        assert!(f.is_well_formed());
+        assert_eq!(f, snapshot.map(|x| x + 100));
+        assert_eq!(snapshot, (0..30).rev().collect::<Fral<usize>>());
+        assert!(f.get_mut(30).is_none());
+
+        // once uniquely held, the same element is mutated in place.
+        let before: *const usize = f.get_mut(17).unwrap();
+        *f.get_mut(17).unwrap() = 0;
+        assert!(std::ptr::eq(before, f.get_ref(17).unwrap()));
+    }
+    #[test]
+    fn get_mut_unique() {
+        let mut f: Fral<str> = Fral::new();
+        for i in 0..30 {
+            f = f.cons(Arc::from(i.to_string()));
+        }
+        // each cons shares the rest of the list with the previous version, which is now gone.
+        for i in 0..30 {
+            f.get_mut_unique(i).unwrap().make_ascii_uppercase();
+        }
+        let snapshot = f.clone();
+        assert!((0..30).all(|i| f.get_mut_unique(i).is_none()));
+        let g = f.update(3, Arc::from("x")).unwrap();
+        drop(snapshot);
+        // the path to 3 was copied, but the rest of the tree is still shared with `g`.
+        assert!(f.get_mut_unique(3).is_some());
+        let shared_by_g = (0..30).filter(|&i| f.get_mut_unique(i).is_none()).count();
+        assert!(shared_by_g > 0);
+        drop(g);
+        assert!((0..30).all(|i| f.get_mut_unique(i).is_some()));
+        assert!(f.get_mut_unique(30).is_none());
+    }
+    #[test]
+    fn cons_mut_and_pop_front() {
+        let mut f = Fral::new();
+        let mut versions = Vec::new();
+        for x in 0..20usize {
+            versions.push(f.clone());
+            f.cons_mut(x);
+            assert!(f.is_well_formed());
+        }
+        for x in (0..20).rev() {
+            assert_eq!(f.pop_front(), Some(Arc::new(x)));
+            assert_eq!(f, versions[x]);
+        }
+        assert_eq!(f.pop_front(), None);
+    }
+    #[test]
+    fn at() {
+        let f: Fral<usize> = (0..40).rev().collect();
+        for i in 0..40 {
+            let at = f.at(i).unwrap();
+            assert_eq!(at.index(), i);
+            assert_eq!(at.get(), Arc::new(i));
+            let g = at.set(100);
+            assert!(g.is_well_formed());
+            assert_eq!(g, f.update(i, 100).unwrap());
+            assert_eq!(at.modify(|x| x + 1), f.update(i, i + 1).unwrap());
         }
+        assert!(f.at(40).is_none());
+    }
+    #[test]
+    fn unsized_elements() {
+        let f: Fral<str> = vec!["c", "b"].into_iter().map(Arc::from).collect();
+        let f = f.cons_from(String::from("a"));
+        assert!(f.is_well_formed());
+        assert_eq!(f.iter().map(|x| x.len()).sum::<usize>(), 3);
+        assert_eq!(&*f.get(2).unwrap(), "c");
+        let g: Fral<str> = vec!["a", "x", "c"]
+            .into_iter()
+            .rev()
+            .map(Arc::from)
+            .collect();
+        assert_eq!(f.update(1, Arc::from("x")).unwrap(), g);
+
+        let g: Fral<[u8]> = Fral::new().cons_from(vec![2, 3]).cons_from(&[1][..]);
+        assert_eq!(g.iter().map(|x| x.len()).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(&g.get(1).unwrap()[..], &[2, 3]);
+    }
+    #[test]
+    fn as_arc_conversions() {
+        let x = vec![1, 2];
+        let f: Fral<Vec<u8>> = Fral::new().cons_cloned(&x).cons_from(Box::new(vec![3]));
         assert_eq!(
-            f.iter().collect::<Vec<_>>(),
-            vec![
-                Arc::new(5),
-                Arc::new(4),
-                Arc::new(3),
-                Arc::new(2),
-                Arc::new(1),
-            ]
+            f,
+            vec![vec![1, 2], vec![3]]
+                .into_iter()
+                .collect::<Fral<Vec<u8>>>()
+        );
+        let g: Fral<[u8]> = Fral::new()
+            .cons_from(x)
+            .cons_from(Box::<[u8]>::from(&[3][..]));
+        assert_eq!(&g[0], &[3]);
+        assert_eq!(&g[1], &[1, 2]);
+        let h: Fral<str> = Fral::new().cons_from(Box::<str>::from("a"));
+        assert_eq!(&h[0], "a");
+    }
+    #[test]
+    fn position_by_ptr() {
+        let xs: Vec<_> = (0..30).map(Arc::new).collect();
+        let f: Fral<usize> = xs.iter().rev().cloned().collect();
+        for (i, x) in xs.iter().enumerate() {
+            assert_eq!(f.position_by_ptr(x), Some(i));
+        }
+        assert_eq!(f.position_by_ptr(&Arc::new(0)), None);
+        let g = f.cons(xs[5].clone());
+        assert_eq!(g.position_by_ptr(&xs[5]), Some(0));
+    }
+    #[test]
+    fn ptr_eq() {
+        let f: Fral<usize> = (0..20).collect();
+        let g = f.clone();
+        assert!(f.ptr_eq(&g) && f == g);
+        assert!(!f.ptr_eq(&(0..20).collect()));
+        assert!(!f.ptr_eq(&f.tail().unwrap()));
+        assert!(Fral::<u8>::new().ptr_eq(&Fral::new()));
+        assert!(!Fral::new().ptr_eq(&f));
+
+        // being the same version does not make elements equal.
+        let nan: Fral<f64> = Fral::new().cons(f64::NAN);
+        assert!(nan.ptr_eq(&nan.clone()) && nan != nan.clone());
+    }
+    #[test]
+    fn sharing() {
+        let f: Fral<usize> = (0..40).collect();
+        assert!(f.is_unique());
+        let report = f.sharing_report();
+        // 40 = 1 + 1 + 7 + 31 takes four spine cells.
+        assert_eq!(
+            (report.nodes, report.shared_nodes, report.shared_elements),
+            (44, 0, 0)
         );
+
+        let g = f.clone();
+        assert!(!f.is_unique());
+        assert_eq!(f.sharing_report().shared_nodes, 44);
+        drop(g);
+
+        let g = f.update(20, 0).unwrap();
+        assert!(!f.is_unique() && !g.is_unique());
+        let report = g.sharing_report();
+        assert_eq!(report.shared_elements, 39);
+        assert_eq!(report.unique_nodes(), f.sharing_report().unique_nodes());
+        drop(g);
+        assert!(f.is_unique());
+
+        let x = f.get(5).unwrap();
+        assert!(!f.is_unique());
+        assert_eq!(f.sharing_report().shared_elements, 1);
+        drop(x);
+        assert!(Fral::<u8>::new().is_unique());
+        assert_eq!(Fral::<u8>::new().sharing_report(), Default::default());
+    }
+    #[test]
+    fn from_array() {
+        let f = Fral::from([0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert!(f.is_well_formed());
+        assert_eq!(f, (0..10).rev().collect::<Fral<usize>>());
+        let empty: [u8; 0] = [];
+        assert!(Fral::from(empty).is_empty());
+    }
+    #[test]
+    fn iter_ref() {
+        for n in 0..70 {
+            let f: Fral<usize> = (0..n).rev().collect();
+            let mut iter = f.iter_ref();
+            assert_eq!(iter.len(), n);
+            for i in 0..n {
+                assert!(std::ptr::eq(iter.next().unwrap(), f.get_ref(i).unwrap()));
+                assert_eq!(iter.len(), n - i - 1);
+            }
+            assert!(iter.next().is_none());
+        }
+    }
+    #[test]
+    fn iter_double_ended() {
+        for n in 0..40 {
+            let f: Fral<usize> = (0..n).rev().collect();
+            let rev: Vec<_> = f.iter().rev().map(|x| *x).collect();
+            assert_eq!(rev, (0..n).rev().collect::<Vec<_>>());
+            assert_eq!(f.iter().last(), f.last());
+
+            // alternate ends, meeting in the middle.
+            let mut iter = f.iter();
+            let (mut lo, mut hi) = (0, n);
+            while lo < hi {
+                assert_eq!(iter.next(), Some(Arc::new(lo)));
+                lo += 1;
+                if lo < hi {
+                    hi -= 1;
+                    assert_eq!(iter.next_back(), Some(Arc::new(hi)));
+                }
+                assert_eq!(iter.len(), hi - lo);
+            }
+            assert_eq!(iter.next(), None);
+            assert_eq!(iter.next_back(), None);
+        }
+    }
+    #[test]
+    fn iter_from() {
+        let f: Fral<usize> = (0..40).rev().collect();
+        for i in 0..42 {
+            let iter = f.iter_from(i);
+            assert_eq!(iter.len(), 40 - i.min(40));
+            assert!(iter.map(|x| *x).eq(i..40));
+        }
+        assert!(f.iter_from(10).rev().map(|x| *x).eq((10..40).rev()));
+    }
+    #[test]
+    fn iter_nth() {
+        let f: Fral<usize> = (0..40).rev().collect();
+        for n in 0..42 {
+            let mut iter = f.iter();
+            assert_eq!(iter.nth(n), f.get(n));
+            assert!(iter.map(|x| *x).eq(n.min(39) + 1..40));
+        }
+        let mut iter = f.iter();
+        assert_eq!(iter.next_back(), Some(Arc::new(39)));
+        assert_eq!(iter.nth(37), Some(Arc::new(37)));
+        assert_eq!(iter.nth(1), None);
+        assert_eq!(iter.next_back(), None);
+        assert!(f.iter().skip(30).map(|x| *x).eq(30..40));
+    }
+    #[test]
+    fn element_type_inference() {
+        // owned values convert only to themselves, so the element type can be left to inference.
+        let f: Fral<_> = vec![String::from("a")].into_iter().collect();
+        assert_eq!(*f.get(0).unwrap(), "a");
+        let g = Fral::new().cons(vec![1u8]);
+        assert_eq!(*g.get(0).unwrap(), vec![1]);
+        let v = [1, 2];
+        let h: Fral<_> = v.iter().collect();
+        assert_eq!(**h.get(0).unwrap(), 2);
+        let h = Fral::new().cons(&v[0]);
+        assert_eq!(**h.get(0).unwrap(), 1);
+        let b = Fral::new().cons(Box::new(3));
+        assert_eq!(**b.get(0).unwrap(), 3);
     }
 }
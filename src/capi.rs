@@ -0,0 +1,181 @@
+//! A C interface to the [`Arc`]-based [`Fral`], enabled with the `capi` feature.
+//!
+//! Lists are passed around as opaque `FralHandle` pointers, each of which owns one reference to
+//! a version of a list. Elements are untyped `void *` pointers: the list only stores them, so the
+//! host is responsible for keeping whatever they point to alive while any list holds them.
+//!
+//! Every handle returned by this interface must eventually be released with [`fral_free`].
+//!
+//! ```c
+//! FralHandle *empty = fral_new();
+//! FralHandle *f = fral_cons(empty, &x);
+//! fral_free(empty);
+//!
+//! void *head;
+//! if (fral_get(f, 0, &head)) {
+//!     assert(head == &x);
+//! }
+//! fral_free(f);
+//! ```
+//!
+//! [`Arc`]: https://doc.rust-lang.org/stable/std/sync/struct.Arc.html
+//! [`Fral`]: ../struct.Fral.html
+//! [`fral_free`]: fn.fral_free.html
+
+use std::os::raw::c_void;
+use Fral;
+
+/// An element stored on behalf of the host.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Item(*mut c_void);
+
+/// An opaque handle to a version of a list.
+pub struct FralHandle(Fral<Item>);
+
+fn into_handle(fral: Fral<Item>) -> *mut FralHandle {
+    Box::into_raw(Box::new(FralHandle(fral)))
+}
+
+/// Construct an empty list.
+#[no_mangle]
+pub extern "C" fn fral_new() -> *mut FralHandle {
+    into_handle(Fral::new())
+}
+
+/// Get another handle to the same version of the list.
+///
+/// Time: O(1)
+///
+/// # Safety
+///
+/// `fral` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn fral_clone(fral: *const FralHandle) -> *mut FralHandle {
+    into_handle((*fral).0.clone())
+}
+
+/// Release a handle. Passing `NULL` does nothing.
+///
+/// # Safety
+///
+/// `fral` must be `NULL` or a live handle, which must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn fral_free(fral: *mut FralHandle) {
+    if !fral.is_null() {
+        drop(Box::from_raw(fral));
+    }
+}
+
+/// Insert an element at the front of the list, returning a handle to the new list.
+///
+/// Time: O(1)
+///
+/// # Safety
+///
+/// `fral` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn fral_cons(fral: *const FralHandle, x: *mut c_void) -> *mut FralHandle {
+    into_handle((*fral).0.cons(Item(x)))
+}
+
+/// Get the head and tail of the list. If the list is nonempty, writes the head to `head` and a
+/// new handle for the tail to `tail` and returns true; otherwise returns false and writes
+/// nothing. Either output pointer may be `NULL` if that output is not needed.
+///
+/// Time: O(1)
+///
+/// # Safety
+///
+/// `fral` must be a live handle, and `head` and `tail` must each be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn fral_uncons(
+    fral: *const FralHandle,
+    head: *mut *mut c_void,
+    tail: *mut *mut FralHandle,
+) -> bool {
+    match (*fral).0.uncons() {
+        Some((x, rest)) => {
+            if !head.is_null() {
+                *head = x.0;
+            }
+            if !tail.is_null() {
+                *tail = into_handle(rest);
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+/// Look up an element. If `index` is in bounds, writes the element to `out` and returns true;
+/// otherwise returns false and writes nothing.
+///
+/// Time: O(log n)
+///
+/// # Safety
+///
+/// `fral` must be a live handle and `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn fral_get(
+    fral: *const FralHandle,
+    index: usize,
+    out: *mut *mut c_void,
+) -> bool {
+    match (*fral).0.get(index) {
+        Some(x) => {
+            *out = x.0;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Get the number of items in the list.
+///
+/// Time: O(1)
+///
+/// # Safety
+///
+/// `fral` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn fral_len(fral: *const FralHandle) -> usize {
+    (*fral).0.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    #[test]
+    fn roundtrip() {
+        let mut items = [1u8, 2, 3];
+        unsafe {
+            let empty = fral_new();
+            let mut f = fral_clone(empty);
+            for x in items.iter_mut() {
+                let g = fral_cons(f, x as *mut u8 as *mut c_void);
+                fral_free(f);
+                f = g;
+            }
+            assert_eq!(fral_len(empty), 0);
+            assert_eq!(fral_len(f), 3);
+
+            let mut out = ptr::null_mut();
+            assert!(fral_get(f, 2, &mut out));
+            assert_eq!(*(out as *mut u8), 1);
+            assert!(!fral_get(f, 3, &mut out));
+
+            let mut tail = ptr::null_mut();
+            assert!(fral_uncons(f, &mut out, &mut tail));
+            assert_eq!(*(out as *mut u8), 3);
+            assert_eq!(fral_len(tail), 2);
+            assert!(!fral_uncons(empty, ptr::null_mut(), ptr::null_mut()));
+
+            fral_free(tail);
+            fral_free(f);
+            fral_free(empty);
+            fral_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,161 @@
+//! A persistent two-dimensional grid, stored as a list of rows.
+//!
+//! Like [`Fral`], a [`Grid`] is never modified in place: [`set`] returns a new grid sharing all
+//! unchanged rows and cells with the original, so snapshots are as cheap as a clone.
+//!
+//! # Examples
+//!
+//! ```
+//! use fral::grid::Grid;
+//!
+//! let board = Grid::new(3, 3, '.');
+//! let next = board.set(1, 1, 'x').unwrap();
+//! assert_eq!(*next.get(1, 1).unwrap(), 'x');
+//! assert_eq!(*board.get(1, 1).unwrap(), '.');
+//! assert_eq!(next.column(1).unwrap().map(|x| *x).collect::<String>(), ".x.");
+//! ```
+//!
+//! [`Fral`]: ../struct.Fral.html
+//! [`Grid`]: struct.Grid.html
+//! [`set`]: struct.Grid.html#method.set
+
+use std::fmt;
+use std::sync::Arc;
+use {AsArc, Fral, Iter};
+
+/// A persistent row-major grid with a fixed number of rows and columns.
+pub struct Grid<T: ?Sized> {
+    rows: Fral<Fral<T>>,
+    height: usize,
+    width: usize,
+}
+impl<T: ?Sized> Grid<T> {
+    /// Construct a grid with every cell holding `fill`. All of the cells, and all of the rows,
+    /// share one allocation.
+    ///
+    /// Time: O(height + width)
+    pub fn new<R: AsArc<T>>(height: usize, width: usize, fill: R) -> Grid<T> {
+        let fill = fill.as_arc();
+        let row: Fral<T> = (0..width).map(|_| fill.clone()).collect();
+        let row = Arc::new(row);
+        Grid {
+            rows: (0..height).map(|_| row.clone()).collect(),
+            height,
+            width,
+        }
+    }
+    /// Construct a grid whose cell in row `r` and column `c` is `f(r, c)`.
+    ///
+    /// Time: O(height * width)
+    pub fn from_fn<F, R>(height: usize, width: usize, mut f: F) -> Grid<T>
+    where
+        F: FnMut(usize, usize) -> R,
+        R: AsArc<T>,
+    {
+        // lists are collected back to front.
+        let rows: Vec<Fral<T>> = (0..height)
+            .map(|r| {
+                let row: Vec<_> = (0..width).map(|c| f(r, c).as_arc()).collect();
+                row.into_iter().rev().collect()
+            })
+            .collect();
+        Grid {
+            rows: rows.into_iter().rev().collect(),
+            height,
+            width,
+        }
+    }
+    /// Get the number of rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    /// Get the number of columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    /// Returns the cell in row `r` and column `c`, or `None` if it is out of bounds.
+    ///
+    /// Time: O(log height + log width)
+    pub fn get(&self, r: usize, c: usize) -> Option<Arc<T>> {
+        self.rows.get(r).and_then(|row| row.get(c))
+    }
+    /// Replace the cell in row `r` and column `c`, or return `None` if it is out of bounds.
+    ///
+    /// Time: O(log height + log width)
+    pub fn set<R: AsArc<T>>(&self, r: usize, c: usize, x: R) -> Option<Grid<T>> {
+        let row = self.rows.get(r)?.update(c, x)?;
+        Some(Grid {
+            rows: self.rows.update(r, row)?,
+            height: self.height,
+            width: self.width,
+        })
+    }
+    /// Iterate over the cells of row `r`, or return `None` if it is out of bounds.
+    pub fn row(&self, r: usize) -> Option<Iter<T>> {
+        self.rows.get(r).map(|row| row.iter())
+    }
+    /// Iterate over the rows, each of which is an iterator over its cells.
+    pub fn rows(&self) -> impl Iterator<Item = Iter<T>> {
+        self.rows.iter().map(|row| row.iter())
+    }
+    /// Iterate over the cells of column `c`, from the first row to the last, or return `None` if
+    /// it is out of bounds.
+    pub fn column(&self, c: usize) -> Option<impl Iterator<Item = Arc<T>>> {
+        if c < self.width {
+            Some(self.rows.iter().map(move |row| row.get(c).unwrap()))
+        } else {
+            None
+        }
+    }
+}
+impl<T: ?Sized> Clone for Grid<T> {
+    fn clone(&self) -> Grid<T> {
+        Grid {
+            rows: self.rows.clone(),
+            height: self.height,
+            width: self.width,
+        }
+    }
+}
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list()
+            .entries(self.rows.iter().map(|row| row.iter().collect::<Vec<_>>()))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_and_snapshots() {
+        let g = Grid::from_fn(4, 5, |r, c| 10 * r + c);
+        assert_eq!((g.height(), g.width()), (4, 5));
+        assert_eq!(g.get(3, 4), Some(Arc::new(34)));
+        assert_eq!(g.get(4, 0), None);
+        assert_eq!(g.get(0, 5), None);
+
+        let h = g.set(2, 3, 0).unwrap();
+        assert_eq!(h.get(2, 3), Some(Arc::new(0)));
+        assert_eq!(g.get(2, 3), Some(Arc::new(23)));
+        assert!(g.set(0, 5, 0).is_none());
+        assert!(g.set(4, 0, 0).is_none());
+
+        let row: Vec<_> = h.row(2).unwrap().map(|x| *x).collect();
+        assert_eq!(row, vec![20, 21, 22, 0, 24]);
+        let column: Vec<_> = h.column(3).unwrap().map(|x| *x).collect();
+        assert_eq!(column, vec![3, 13, 0, 33]);
+        assert!(h.row(4).is_none());
+        assert!(h.column(5).is_none());
+        assert_eq!(h.rows().flatten().count(), 20);
+    }
+    #[test]
+    fn new_shares_fill() {
+        let g = Grid::new(3, 2, vec![1]);
+        let cells: Vec<_> = g.rows().flatten().collect();
+        assert_eq!(cells.len(), 6);
+        assert!(cells.iter().all(|x| Arc::ptr_eq(x, &cells[0])));
+    }
+}
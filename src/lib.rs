@@ -83,7 +83,36 @@
 //! [`im::CatList`]: https://docs.rs/im/~10.0/im/catlist/struct.CatList.html
 //! [`im::ConsList`]: https://docs.rs/im/~10.0/im/conslist/struct.ConsList.html
 
+// the pyo3 macros refer to `::core`, which is not in scope by default before the 2018 edition.
+#[cfg(feature = "python")]
+extern crate core;
+#[cfg(all(test, feature = "async"))]
+extern crate futures;
+#[cfg(feature = "async")]
+extern crate futures_core;
+#[cfg(feature = "memchr")]
+extern crate memchr;
+#[cfg(feature = "python")]
+extern crate pyo3;
+#[cfg(feature = "rand")]
+extern crate rand;
+#[cfg(feature = "wasm")]
+extern crate wasm_bindgen;
+
+#[macro_use]
+mod macros;
+
 mod arc;
+#[cfg(feature = "capi")]
+pub mod capi;
+pub mod grid;
+#[cfg(feature = "python")]
+pub mod python;
 pub mod rc;
+pub mod testing;
+#[cfg(kani)]
+mod verification;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 pub use arc::*;
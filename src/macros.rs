@@ -0,0 +1,71 @@
+//! Construction macros, in the style of `vec!`.
+
+/// Construct a [`Fral`] with the elements in the written order, or with `n` copies of an element.
+///
+/// Unlike collecting an iterator, which conses each element in turn and so reverses it, the first
+/// element written is at the front of the list. The repeat form uses [`Fral::repeat`], so the
+/// element is shared rather than cloned. For an [`rc::Fral`], use [`rc_fral!`].
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate fral;
+/// # use std::sync::Arc;
+/// # fn main() {
+/// let f = fral![1, 2, 3];
+/// assert_eq!(f.get(0), Some(Arc::new(1)));
+/// assert_eq!(fral!["a"; 4].len(), 4);
+/// assert!(fral![].cons(0).len() == 1);
+/// # }
+/// ```
+///
+/// [`Fral`]: struct.Fral.html
+/// [`Fral::repeat`]: struct.Fral.html#method.repeat
+/// [`rc::Fral`]: rc/struct.Fral.html
+/// [`rc_fral!`]: macro.rc_fral.html
+#[macro_export]
+macro_rules! fral {
+    () => {
+        $crate::Fral::new()
+    };
+    ($x:expr; $n:expr) => {
+        $crate::Fral::repeat($x, $n)
+    };
+    ($($x:expr),+ $(,)*) => {
+        <$crate::Fral<_> as ::std::iter::FromIterator<_>>::from_iter(
+            vec![$($x),+].into_iter().rev(),
+        )
+    };
+}
+
+/// Construct an [`rc::Fral`] with the elements in the written order, or with `n` copies of an
+/// element. This is the [`rc::Fral`] counterpart of [`fral!`].
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate fral;
+/// # use std::rc::Rc;
+/// # fn main() {
+/// let f = rc_fral![1, 2, 3];
+/// assert_eq!(f.get(2), Some(Rc::new(3)));
+/// assert_eq!(rc_fral![0; 5].len(), 5);
+/// # }
+/// ```
+///
+/// [`rc::Fral`]: rc/struct.Fral.html
+/// [`fral!`]: macro.fral.html
+#[macro_export]
+macro_rules! rc_fral {
+    () => {
+        $crate::rc::Fral::new()
+    };
+    ($x:expr; $n:expr) => {
+        $crate::rc::Fral::repeat($x, $n)
+    };
+    ($($x:expr),+ $(,)*) => {
+        <$crate::rc::Fral<_> as ::std::iter::FromIterator<_>>::from_iter(
+            vec![$($x),+].into_iter().rev(),
+        )
+    };
+}
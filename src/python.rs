@@ -0,0 +1,143 @@
+//! Python bindings via [PyO3], enabled with the `python` feature.
+//!
+//! The [`Arc`]-based [`Fral`] of Python objects is exposed as the `Fral` class. Add it to your
+//! own extension module with [`register`]:
+//!
+//! ```ignore
+//! #[pymodule]
+//! fn mymodule(m: &Bound<'_, PyModule>) -> PyResult<()> {
+//!     fral::python::register(m)
+//! }
+//! ```
+//!
+//! ```python
+//! from mymodule import Fral
+//!
+//! empty = Fral()
+//! f = empty.cons(1).cons(2)
+//! assert f[0] == 2 and f[-1] == 1
+//! assert list(f) == [2, 1]
+//! assert len(empty) == 0  # the original list is unchanged
+//! ```
+//!
+//! [PyO3]: https://pyo3.rs
+//! [`Arc`]: https://doc.rust-lang.org/stable/std/sync/struct.Arc.html
+//! [`Fral`]: ../struct.Fral.html
+//! [`register`]: fn.register.html
+
+use pyo3::exceptions::PyIndexError;
+use pyo3::prelude::*;
+use std::sync::Mutex;
+use {Fral, Iter};
+
+/// Add the `Fral` class to a Python module.
+pub fn register(m: &Bound<PyModule>) -> PyResult<()> {
+    m.add_class::<PyFral>()
+}
+
+/// A functional random access list of Python objects, exposed as `Fral`.
+#[pyclass(name = "Fral", module = "fral", frozen, from_py_object)]
+#[derive(Clone, Default)]
+pub struct PyFral {
+    fral: Fral<Py<PyAny>>,
+}
+
+#[pymethods]
+impl PyFral {
+    /// Construct an empty list.
+    #[new]
+    pub fn new() -> PyFral {
+        PyFral::default()
+    }
+    /// Insert an element at the front of the list.
+    ///
+    /// Time: O(1)
+    pub fn cons(&self, x: Py<PyAny>) -> PyFral {
+        PyFral {
+            fral: self.fral.cons(x),
+        }
+    }
+    /// Get the head and tail of the list, or `None` if it is empty.
+    ///
+    /// Time: O(1)
+    pub fn uncons(&self, py: Python) -> Option<(Py<PyAny>, PyFral)> {
+        self.fral
+            .uncons()
+            .map(|(x, fral)| (x.clone_ref(py), PyFral { fral }))
+    }
+    /// Get another handle to the same list, sharing all of its structure.
+    ///
+    /// Time: O(1)
+    pub fn snapshot(&self) -> PyFral {
+        self.clone()
+    }
+    fn __copy__(&self) -> PyFral {
+        self.clone()
+    }
+    fn __len__(&self) -> usize {
+        self.fral.len()
+    }
+    fn __bool__(&self) -> bool {
+        !self.fral.is_empty()
+    }
+    /// Look up an element, counting from the back for negative indices.
+    ///
+    /// Time: O(log n)
+    fn __getitem__(&self, py: Python, index: isize) -> PyResult<Py<PyAny>> {
+        let len = self.fral.len();
+        let index = if index < 0 {
+            index + len as isize
+        } else {
+            index
+        };
+        if index < 0 {
+            return Err(PyIndexError::new_err("Fral index out of range"));
+        }
+        self.fral
+            .get(index as usize)
+            .map(|x| x.clone_ref(py))
+            .ok_or_else(|| PyIndexError::new_err("Fral index out of range"))
+    }
+    fn __iter__(&self) -> PyFralIter {
+        PyFralIter {
+            iter: Mutex::new(self.fral.iter()),
+        }
+    }
+    fn __repr__(&self, py: Python) -> PyResult<String> {
+        let items = self
+            .fral
+            .iter()
+            .map(|x| Ok(x.bind(py).repr()?.to_string()))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(format!("Fral([{}])", items.join(", ")))
+    }
+}
+
+impl From<Fral<Py<PyAny>>> for PyFral {
+    fn from(fral: Fral<Py<PyAny>>) -> PyFral {
+        PyFral { fral }
+    }
+}
+
+impl From<PyFral> for Fral<Py<PyAny>> {
+    fn from(f: PyFral) -> Fral<Py<PyAny>> {
+        f.fral
+    }
+}
+
+/// The iterator returned by `iter(Fral)`.
+#[pyclass(module = "fral", frozen)]
+pub struct PyFralIter {
+    iter: Mutex<Iter<Py<PyAny>>>,
+}
+
+#[pymethods]
+impl PyFralIter {
+    fn __iter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+    fn __next__(&self, py: Python) -> Option<Py<PyAny>> {
+        let mut iter = self.iter.lock().unwrap();
+        iter.next().map(|x| x.clone_ref(py))
+    }
+}
@@ -0,0 +1,374 @@
+//! Model-checking utilities for persistent lists.
+//!
+//! A script is a sequence of [`Op`]s which is run against both a [`PersistentList`]
+//! implementation and a simple `Vec`-based reference model. Every intermediate version is kept
+//! around, so scripts also check that older versions are left untouched by later operations.
+//! When a script fails, it is minimized before being reported.
+//!
+//! # Examples
+//!
+//! ```
+//! use fral::Fral;
+//! use fral::testing;
+//!
+//! // run 100 random scripts of 50 operations each
+//! let result = testing::check_random::<Fral<u32>, _>(42, 100, 50, |x| x as u32);
+//! assert!(result.is_ok());
+//! ```
+//!
+//! [`Op`]: enum.Op.html
+//! [`PersistentList`]: trait.PersistentList.html
+
+use std::fmt;
+use std::fmt::Debug;
+
+/// The operations exercised by the model checker.
+///
+/// This is implemented for [`Fral`] and [`rc::Fral`], and can be implemented for any type
+/// wrapping them.
+///
+/// [`Fral`]: ../struct.Fral.html
+/// [`rc::Fral`]: ../rc/struct.Fral.html
+pub trait PersistentList: Clone {
+    type Item;
+    /// Construct an empty list.
+    fn empty() -> Self;
+    /// Insert an element at the front of the list.
+    fn cons(&self, x: Self::Item) -> Self;
+    /// Get the head and tail of the list.
+    fn uncons(&self) -> Option<(Self::Item, Self)>;
+    /// Returns an element, or `None` if it is out of bounds.
+    fn get(&self, index: usize) -> Option<Self::Item>;
+    /// Get the number of items in the list.
+    fn len(&self) -> usize;
+    /// Returns true iff the list contains no elements.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: Clone> PersistentList for ::Fral<T> {
+    type Item = T;
+    fn empty() -> Self {
+        ::Fral::new()
+    }
+    fn cons(&self, x: T) -> Self {
+        ::Fral::cons(self, x)
+    }
+    fn uncons(&self) -> Option<(T, Self)> {
+        ::Fral::uncons(self).map(|(x, f)| ((*x).clone(), f))
+    }
+    fn get(&self, index: usize) -> Option<T> {
+        ::Fral::get(self, index).map(|x| (*x).clone())
+    }
+    fn len(&self) -> usize {
+        ::Fral::len(self)
+    }
+}
+
+impl<T: Clone> PersistentList for ::rc::Fral<T> {
+    type Item = T;
+    fn empty() -> Self {
+        ::rc::Fral::new()
+    }
+    fn cons(&self, x: T) -> Self {
+        ::rc::Fral::cons(self, x)
+    }
+    fn uncons(&self) -> Option<(T, Self)> {
+        ::rc::Fral::uncons(self).map(|(x, f)| ((*x).clone(), f))
+    }
+    fn get(&self, index: usize) -> Option<T> {
+        ::rc::Fral::get(self, index).map(|x| (*x).clone())
+    }
+    fn len(&self) -> usize {
+        ::rc::Fral::len(self)
+    }
+}
+
+/// A single step of a script.
+///
+/// Each operation acts on the _current_ version and produces a new current version. Every
+/// version is retained, and [`Checkout`] makes an earlier version current again.
+///
+/// [`Checkout`]: #variant.Checkout
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Op<T> {
+    /// `cons` the element onto the current version.
+    Cons(T),
+    /// `uncons` the current version, if it is nonempty.
+    Uncons,
+    /// `get` the element at the index, which may be out of bounds.
+    Get(usize),
+    /// Make the version with this number (modulo the number of versions) current.
+    Checkout(usize),
+}
+
+/// A failing script, as reported by [`check`] and [`check_random`].
+///
+/// [`check`]: fn.check.html
+/// [`check_random`]: fn.check_random.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Failure<T> {
+    /// The (minimized) script.
+    pub script: Vec<Op<T>>,
+    /// The number of operations of the script that were run before the failure was observed.
+    pub step: usize,
+    /// A description of the mismatch.
+    pub message: String,
+}
+
+impl<T: Debug> fmt::Display for Failure<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "after step {}: {}", self.step, self.message)?;
+        for (i, op) in self.script.iter().enumerate() {
+            writeln!(f, "  {:>4}: {:?}", i, op)?;
+        }
+        Ok(())
+    }
+}
+
+/// Run a script against the list implementation and the reference model, minimizing it if it
+/// fails.
+pub fn check<L>(script: &[Op<L::Item>]) -> Result<(), Failure<L::Item>>
+where
+    L: PersistentList,
+    L::Item: Clone + PartialEq + Debug,
+{
+    match run::<L>(script) {
+        Ok(()) => Ok(()),
+        Err(_) => Err(minimize::<L>(script.to_vec())),
+    }
+}
+
+/// Generate and check `runs` random scripts, each with `len` operations.
+///
+/// The scripts are determined by `seed`, and elements are created by `gen` from random numbers.
+/// The first failing script is minimized and returned.
+pub fn check_random<L, F>(
+    seed: u64,
+    runs: usize,
+    len: usize,
+    mut gen: F,
+) -> Result<(), Failure<L::Item>>
+where
+    L: PersistentList,
+    L::Item: Clone + PartialEq + Debug,
+    F: FnMut(u64) -> L::Item,
+{
+    let mut rng = XorShift::new(seed);
+    for _ in 0..runs {
+        let script = random_script_with(&mut rng, len, &mut gen);
+        check::<L>(&script)?;
+    }
+    Ok(())
+}
+
+/// Generate a random script with `len` operations. Conses are twice as likely as any other
+/// operation so the lists tend to grow.
+pub fn random_script<T, F>(seed: u64, len: usize, mut gen: F) -> Vec<Op<T>>
+where
+    F: FnMut(u64) -> T,
+{
+    random_script_with(&mut XorShift::new(seed), len, &mut gen)
+}
+
+fn random_script_with<T, F>(rng: &mut XorShift, len: usize, gen: &mut F) -> Vec<Op<T>>
+where
+    F: FnMut(u64) -> T,
+{
+    (0..len)
+        .map(|_| match rng.next() % 5 {
+            0 | 1 => Op::Cons(gen(rng.next())),
+            2 => Op::Uncons,
+            3 => Op::Get((rng.next() % 64) as usize),
+            _ => Op::Checkout(rng.next() as usize),
+        })
+        .collect()
+}
+
+/// Run the script, returning the step and description of the first mismatch.
+fn run<L>(script: &[Op<L::Item>]) -> Result<(), (usize, String)>
+where
+    L: PersistentList,
+    L::Item: Clone + PartialEq + Debug,
+{
+    // the model stores the list back-to-front, so cons is push.
+    let mut versions: Vec<(L, Vec<L::Item>)> = vec![(L::empty(), Vec::new())];
+    let mut current = 0;
+    for (step, op) in script.iter().enumerate() {
+        let (list, model) = versions[current].clone();
+        match *op {
+            Op::Cons(ref x) => {
+                let list = list.cons(x.clone());
+                let mut model = model;
+                model.push(x.clone());
+                versions.push((list, model));
+                current = versions.len() - 1;
+            }
+            Op::Uncons => match (list.uncons(), model.last()) {
+                (None, None) => (),
+                (Some((x, list)), Some(y)) => {
+                    if x != *y {
+                        return Err((step, format!("uncons gave head {:?}, expected {:?}", x, y)));
+                    }
+                    let model = model[..model.len() - 1].to_vec();
+                    versions.push((list, model));
+                    current = versions.len() - 1;
+                }
+                (x, y) => {
+                    let x = x.map(|(x, _)| x);
+                    return Err((step, format!("uncons gave {:?}, expected {:?}", x, y)));
+                }
+            },
+            Op::Get(index) => {
+                let x = list.get(index);
+                let y = if index < model.len() {
+                    Some(model[model.len() - 1 - index].clone())
+                } else {
+                    None
+                };
+                if x != y {
+                    return Err((
+                        step,
+                        format!("get({}) gave {:?}, expected {:?}", index, x, y),
+                    ));
+                }
+            }
+            Op::Checkout(n) => current = n % versions.len(),
+        }
+        for (v, (list, model)) in versions.iter().enumerate() {
+            if let Err(message) = compare(list, model) {
+                return Err((step + 1, format!("version {}: {}", v, message)));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn compare<L>(list: &L, model: &[L::Item]) -> Result<(), String>
+where
+    L: PersistentList,
+    L::Item: PartialEq + Debug,
+{
+    if list.len() != model.len() {
+        return Err(format!("len is {}, expected {}", list.len(), model.len()));
+    }
+    for (i, y) in model.iter().rev().enumerate() {
+        let x = list.get(i);
+        if x.as_ref() != Some(y) {
+            return Err(format!("get({}) gave {:?}, expected {:?}", i, x, y));
+        }
+    }
+    Ok(())
+}
+
+/// Repeatedly remove operations from a failing script while it keeps failing.
+fn minimize<L>(mut script: Vec<Op<L::Item>>) -> Failure<L::Item>
+where
+    L: PersistentList,
+    L::Item: Clone + PartialEq + Debug,
+{
+    let mut failure = run::<L>(&script).expect_err("script must fail");
+    let mut i = 0;
+    while i < script.len() {
+        let mut candidate = script.clone();
+        candidate.remove(i);
+        match run::<L>(&candidate) {
+            Err(f) => {
+                script = candidate;
+                failure = f;
+                i = 0;
+            }
+            Ok(()) => i += 1,
+        }
+    }
+    let (step, message) = failure;
+    Failure {
+        script,
+        step,
+        message,
+    }
+}
+
+/// A small deterministic generator so scripts are reproducible from a seed.
+struct XorShift(u64);
+
+impl XorShift {
+    fn new(seed: u64) -> XorShift {
+        // xorshift gets stuck at zero
+        match seed ^ 0x9E37_79B9_7F4A_7C15 {
+            0 => XorShift(0x9E37_79B9_7F4A_7C15),
+            state => XorShift(state),
+        }
+    }
+    fn next(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A list which loses every element past the third.
+    #[derive(Clone)]
+    struct Forgetful(::Fral<u8>);
+    impl PersistentList for Forgetful {
+        type Item = u8;
+        fn empty() -> Self {
+            Forgetful(::Fral::new())
+        }
+        fn cons(&self, x: u8) -> Self {
+            Forgetful(self.0.cons(x))
+        }
+        fn uncons(&self) -> Option<(u8, Self)> {
+            self.0.uncons().map(|(x, f)| (*x, Forgetful(f)))
+        }
+        fn get(&self, index: usize) -> Option<u8> {
+            if index < 3 {
+                self.0.get(index).map(|x| *x)
+            } else {
+                None
+            }
+        }
+        fn len(&self) -> usize {
+            self.0.len()
+        }
+    }
+
+    #[test]
+    fn fral_passes() {
+        assert_eq!(
+            check_random::<::Fral<u64>, _>(0, 200, 60, |x| x % 100),
+            Ok(())
+        );
+        assert_eq!(
+            check_random::<::rc::Fral<u64>, _>(1, 200, 60, |x| x % 100),
+            Ok(())
+        );
+    }
+    #[test]
+    fn huge_index() {
+        assert_eq!(check::<::Fral<u64>>(&[Op::Get(usize::MAX)]), Ok(()));
+        assert_eq!(
+            check::<::Fral<u64>>(&[Op::Cons(1), Op::Get(usize::MAX)]),
+            Ok(())
+        );
+    }
+    #[test]
+    fn zero_seed_state() {
+        let mut rng = XorShift::new(0x9E37_79B9_7F4A_7C15);
+        assert_ne!(rng.next(), 0);
+    }
+    #[test]
+    fn failure_is_minimized() {
+        let failure = check_random::<Forgetful, _>(0, 100, 60, |x| x as u8).unwrap_err();
+        assert_eq!(failure.script.len(), 4);
+        assert!(failure.script.iter().all(|op| matches!(*op, Op::Cons(_))));
+    }
+}
@@ -0,0 +1,73 @@
+//! [Kani] proof harnesses for the core invariants, checked up to a bounded list length.
+//!
+//! These are only compiled by `cargo kani`, which sets `cfg(kani)`.
+//!
+//! [Kani]: https://github.com/model-checking/kani
+
+/// The largest list length explored by the harnesses.
+const MAX_LEN: usize = 7;
+
+/// Build a list of nondeterministic length and contents, in written order.
+macro_rules! any_fral {
+    ($fral:ty) => {{
+        let len: usize = kani::any();
+        kani::assume(len <= MAX_LEN);
+        let mut items = [0u8; MAX_LEN];
+        for item in items.iter_mut() {
+            *item = kani::any();
+        }
+        let mut f = <$fral>::new();
+        for &item in items[..len].iter().rev() {
+            f = f.cons(item);
+        }
+        (f, items, len)
+    }};
+}
+
+macro_rules! harnesses {
+    ($module:ident, $fral:ty) => {
+        mod $module {
+            use super::MAX_LEN;
+
+            #[kani::proof]
+            #[kani::unwind(9)]
+            fn cons_uncons_roundtrip() {
+                let (f, _, len) = any_fral!($fral);
+                let x: u8 = kani::any();
+                let g = f.cons(x);
+                assert_eq!(g.len(), len + 1);
+                let (head, tail) = g.uncons().unwrap();
+                assert_eq!(*head, x);
+                assert_eq!(tail, f);
+            }
+
+            #[kani::proof]
+            #[kani::unwind(9)]
+            fn get_matches_items() {
+                let (f, items, len) = any_fral!($fral);
+                let index: usize = kani::any();
+                kani::assume(index <= MAX_LEN);
+                match f.get(index) {
+                    Some(x) => assert!(index < len && *x == items[index]),
+                    None => assert!(index >= len),
+                }
+            }
+
+            #[kani::proof]
+            #[kani::unwind(9)]
+            fn spine_is_skew_binary() {
+                let (f, _, len) = any_fral!($fral);
+                assert!(f.is_well_formed());
+                let mut f = f;
+                for _ in 0..len {
+                    f = f.uncons().unwrap().1;
+                    assert!(f.is_well_formed());
+                }
+                assert!(f.is_empty());
+            }
+        }
+    };
+}
+
+harnesses!(arc, ::Fral<u8>);
+harnesses!(rc, ::rc::Fral<u8>);
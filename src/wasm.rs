@@ -0,0 +1,171 @@
+//! JavaScript bindings via [`wasm-bindgen`], enabled with the `wasm` feature.
+//!
+//! The [`Arc`]-based [`Fral`] of JavaScript values is exported as the `Fral` class:
+//!
+//! ```js
+//! import { Fral } from "fral";
+//!
+//! const empty = new Fral();
+//! const f = empty.cons(1).cons(2);
+//! f.get(0);      // 2
+//! f.length;      // 2
+//! f.toArray();   // [2, 1]
+//! [...f];        // [2, 1]
+//! f.uncons();    // [2, <Fral [1]>]
+//! empty.length;  // 0, the original list is unchanged
+//! ```
+//!
+//! Lists never copy their elements, so snapshots can be shared freely between JavaScript state
+//! and Rust code.
+//!
+//! [`wasm-bindgen`]: https://docs.rs/wasm-bindgen
+//! [`Arc`]: https://doc.rust-lang.org/stable/std/sync/struct.Arc.html
+//! [`Fral`]: ../struct.Fral.html
+
+use wasm_bindgen::prelude::*;
+use {Fral, Iter};
+
+/// A functional random access list of JavaScript values, exported as `Fral`.
+#[wasm_bindgen(js_name = Fral)]
+#[derive(Clone, Default)]
+pub struct JsFral {
+    fral: Fral<JsValue>,
+}
+
+#[wasm_bindgen(js_class = Fral)]
+impl JsFral {
+    /// Construct an empty list.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> JsFral {
+        JsFral::default()
+    }
+    /// Insert an element at the front of the list.
+    ///
+    /// Time: O(1)
+    pub fn cons(&self, x: JsValue) -> JsFral {
+        JsFral {
+            fral: self.fral.cons(x),
+        }
+    }
+    /// Returns the first element, or `undefined` if the list is empty.
+    ///
+    /// Time: O(1)
+    pub fn head(&self) -> JsValue {
+        self.get(0)
+    }
+    /// Returns the head and tail of the list as a `[head, tail]` array, or `undefined` if the
+    /// list is empty.
+    ///
+    /// Time: O(1)
+    pub fn uncons(&self) -> Option<Vec<JsValue>> {
+        self.fral
+            .uncons()
+            .map(|(x, fral)| vec![(*x).clone(), JsFral { fral }.into()])
+    }
+    /// Returns the list without its first element, or `undefined` if the list is empty.
+    ///
+    /// Time: O(1)
+    pub fn tail(&self) -> Option<JsFral> {
+        self.fral.tail().map(|fral| JsFral { fral })
+    }
+    /// Returns an element, or `undefined` if it is out of bounds.
+    ///
+    /// Time: O(log n)
+    pub fn get(&self, index: usize) -> JsValue {
+        self.fral
+            .get(index)
+            .map_or(JsValue::UNDEFINED, |x| (*x).clone())
+    }
+    /// Get the number of items in the list.
+    ///
+    /// Time: O(1)
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.fral.len()
+    }
+    /// Returns true iff the list contains no elements.
+    ///
+    /// Time: O(1)
+    #[wasm_bindgen(js_name = isEmpty)]
+    pub fn is_empty(&self) -> bool {
+        self.fral.is_empty()
+    }
+    /// Get another handle to the same list, sharing all of its structure.
+    ///
+    /// Time: O(1)
+    pub fn snapshot(&self) -> JsFral {
+        self.clone()
+    }
+    /// Iterate over the elements in order, so that lists work with `for...of` and spreading.
+    /// The iterator holds its own snapshot, so later changes elsewhere do not affect it.
+    #[wasm_bindgen(js_name = "[Symbol.iterator]")]
+    pub fn iter(&self) -> JsFralIter {
+        JsFralIter {
+            iter: self.fral.iter(),
+        }
+    }
+    /// Copy the elements, in order, into a new array.
+    ///
+    /// Time: O(n)
+    #[wasm_bindgen(js_name = toArray)]
+    pub fn to_array(&self) -> Vec<JsValue> {
+        self.fral.iter().map(|x| (*x).clone()).collect()
+    }
+}
+
+/// An iterator over the elements of a `Fral`, following the JavaScript iterator protocol.
+#[wasm_bindgen(js_name = FralIterator)]
+pub struct JsFralIter {
+    iter: Iter<JsValue>,
+}
+
+#[wasm_bindgen(js_class = FralIterator)]
+impl JsFralIter {
+    /// Advance the iterator, returning a result with `done` and `value` properties.
+    #[wasm_bindgen(js_name = next)]
+    pub fn next_result(&mut self) -> JsIterResult {
+        match self.iter.next() {
+            Some(x) => JsIterResult {
+                done: false,
+                value: (*x).clone(),
+            },
+            None => JsIterResult {
+                done: true,
+                value: JsValue::UNDEFINED,
+            },
+        }
+    }
+}
+
+/// A step of a `FralIterator`, as returned by its `next` method.
+#[wasm_bindgen(js_name = FralIteratorResult)]
+pub struct JsIterResult {
+    done: bool,
+    value: JsValue,
+}
+
+#[wasm_bindgen(js_class = FralIteratorResult)]
+impl JsIterResult {
+    /// Returns true iff the iterator has no more elements.
+    #[wasm_bindgen(getter)]
+    pub fn done(&self) -> bool {
+        self.done
+    }
+    /// Get the element, or `undefined` once the iterator is done.
+    #[wasm_bindgen(getter)]
+    pub fn value(&self) -> JsValue {
+        self.value.clone()
+    }
+}
+
+impl From<Fral<JsValue>> for JsFral {
+    fn from(fral: Fral<JsValue>) -> JsFral {
+        JsFral { fral }
+    }
+}
+
+impl From<JsFral> for Fral<JsValue> {
+    fn from(f: JsFral) -> Fral<JsValue> {
+        f.fral
+    }
+}